@@ -0,0 +1,169 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::fmt::{Display, Formatter};
+
+use num::BigInt;
+use serde::de::{Error, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// answer of a puzzle part, printed by the runner
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Answer {
+  Int(i128),
+  Str(String),
+}
+
+macro_rules! impl_from_int {
+  ($($ty:ty),*) => {
+    $(
+      impl From<$ty> for Answer {
+        fn from(value: $ty) -> Self {
+          Self::Int(value as i128)
+        }
+      }
+    )*
+  };
+}
+
+impl_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// falls back to the decimal string when it doesn't fit
+impl From<BigInt> for Answer {
+  fn from(value: BigInt) -> Self {
+    i128::try_from(&value).map_or_else(|_| Self::Str(value.to_string()), Self::Int)
+  }
+}
+
+impl From<String> for Answer {
+  fn from(value: String) -> Self {
+    Self::Str(value)
+  }
+}
+
+impl From<&str> for Answer {
+  fn from(value: &str) -> Self {
+    Self::Str(value.to_string())
+  }
+}
+
+impl Display for Answer {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Answer::Int(it) => write!(f, "{it}"),
+      Answer::Str(it) => write!(f, "{it}"),
+    }
+  }
+}
+
+/// integers past 64 bits are written as strings, json readers turn them into floats otherwise
+impl Serialize for Answer {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      Answer::Int(it) => match i64::try_from(*it) {
+        Ok(it) => serializer.serialize_i64(it),
+        Err(_) => match u64::try_from(*it) {
+          Ok(it) => serializer.serialize_u64(it),
+          Err(_) => serializer.serialize_str(&it.to_string()),
+        },
+      },
+      Answer::Str(it) => serializer.serialize_str(it),
+    }
+  }
+}
+
+/// accepts both numbers and strings, `#[serde(untagged)]` can't buffer `i128`
+///
+/// strings of an integer are read as integers, which is how answers past 64 bits are written
+impl<'de> Deserialize<'de> for Answer {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct AnswerVisitor;
+
+    impl Visitor<'_> for AnswerVisitor {
+      type Value = Answer;
+
+      fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "an integer or a string")
+      }
+
+      fn visit_i64<E: Error>(self, v: i64) -> Result<Answer, E> {
+        Ok(v.into())
+      }
+
+      fn visit_u64<E: Error>(self, v: u64) -> Result<Answer, E> {
+        Ok(v.into())
+      }
+
+      fn visit_i128<E: Error>(self, v: i128) -> Result<Answer, E> {
+        Ok(Answer::Int(v))
+      }
+
+      fn visit_u128<E: Error>(self, v: u128) -> Result<Answer, E> {
+        i128::try_from(v)
+          .map(Answer::Int)
+          .map_err(|_| E::custom(format!("answer {v} is too large")))
+      }
+
+      fn visit_str<E: Error>(self, v: &str) -> Result<Answer, E> {
+        Ok(v.parse::<i128>().map_or_else(|_| v.into(), Answer::Int))
+      }
+    }
+
+    deserializer.deserialize_any(AnswerVisitor)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use super::*;
+
+  #[test]
+  fn deserialize_numbers_and_strings() {
+    let answers: BTreeMap<String, Answer> = serde_json::from_str(
+      r#"{ "a": 142, "b": -3, "c": "EZONXCL", "d": "170141183460469231731687303715884105727" }"#,
+    )
+    .unwrap();
+    assert_eq!(answers["a"], Answer::Int(142));
+    assert_eq!(answers["b"], Answer::Int(-3));
+    assert_eq!(answers["c"], Answer::from("EZONXCL"));
+    assert_eq!(answers["d"], Answer::Int(i128::MAX));
+
+    // unquoted it's a float to the json reader
+    assert!(serde_json::from_str::<Answer>("170141183460469231731687303715884105727").is_err());
+    assert_eq!(
+      serde_json::from_str::<Answer>(r#""170141183460469231731687303715884105728""#).unwrap(),
+      Answer::from("170141183460469231731687303715884105728")
+    );
+    assert!(serde_json::from_str::<Answer>("1.5").is_err());
+    assert!(serde_json::from_str::<Answer>("[1]").is_err());
+  }
+
+  #[test]
+  fn serialize_round_trip() {
+    for answer in [
+      Answer::Int(-7),
+      Answer::Int(u64::MAX as i128),
+      Answer::Int(i128::MIN),
+      Answer::from("abc"),
+    ] {
+      let json = serde_json::to_string(&answer).unwrap();
+      assert_eq!(serde_json::from_str::<Answer>(&json).unwrap(), answer);
+    }
+    assert_eq!(serde_json::to_string(&Answer::Int(5)).unwrap(), "5");
+    assert_eq!(
+      serde_json::to_string(&Answer::Int(1 << 64)).unwrap(),
+      r#""18446744073709551616""#
+    );
+  }
+
+  #[test]
+  fn from_big_int() {
+    assert_eq!(Answer::from(BigInt::from(-5)), Answer::Int(-5));
+    let big = BigInt::from(i128::MAX) + 1_u8;
+    assert_eq!(Answer::from(big.clone()), Answer::Str(big.to_string()));
+  }
+}
@@ -0,0 +1,154 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::input::{Inputs, Source};
+use crate::registry;
+use crate::solution::DynSolution;
+
+/// min, median and max of the samples in nanoseconds
+#[derive(Debug, Copy, Clone, Serialize)]
+pub struct Stats {
+  pub min_ns: u128,
+  pub median_ns: u128,
+  pub max_ns: u128,
+}
+
+impl Stats {
+  pub fn new(samples: &[Duration]) -> Self {
+    let samples = samples
+      .iter()
+      .map(Duration::as_nanos)
+      .sorted()
+      .collect_vec();
+    Self {
+      min_ns: samples[0],
+      median_ns: samples[samples.len() / 2],
+      max_ns: samples[samples.len() - 1],
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayBench {
+  pub day: u8,
+  pub title: &'static str,
+  pub parse: Stats,
+  pub part_1: Stats,
+  pub part_2: Stats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+  pub year: u16,
+  pub runs: usize,
+  pub days: Vec<DayBench>,
+}
+
+/// parse the input and run both parts `runs` times, each run starts from a fresh parse
+pub fn bench_day(solution: &dyn DynSolution, input: &str, runs: usize) -> anyhow::Result<DayBench> {
+  let mut samples = [vec![], vec![], vec![]];
+
+  for _ in 0..runs {
+    let start = Instant::now();
+    let parsed = solution.parse(black_box(input))?;
+    samples[0].push(start.elapsed());
+
+    for part in 1..=2 {
+      let start = Instant::now();
+      black_box(parsed.part(part)?);
+      samples[part as usize].push(start.elapsed());
+    }
+  }
+
+  Ok(DayBench {
+    day: solution.day(),
+    title: solution.title(),
+    parse: Stats::new(&samples[0]),
+    part_1: Stats::new(&samples[1]),
+    part_2: Stats::new(&samples[2]),
+  })
+}
+
+/// bench `days` of `year`, or every day which has an input when `days` is empty
+pub fn bench(
+  inputs: &Inputs,
+  source: &Source,
+  year: u16,
+  days: &[u8],
+  runs: usize,
+) -> anyhow::Result<Report> {
+  // stdin can only be read once
+  if *source == Source::Stdin && days.len() != 1 {
+    bail!("`--stdin` needs exactly one day to bench");
+  }
+
+  let solutions = if days.is_empty() {
+    let name = match source {
+      Source::Named(name) => Some(name.as_str()),
+      _ => None,
+    };
+    registry::year(year)?
+      .iter()
+      .copied()
+      .filter(|it| {
+        let has_input = inputs.path(year, it.day(), name).is_file();
+        if !has_input {
+          eprintln!("skipping day {}, no input", it.day());
+        }
+        has_input
+      })
+      .collect_vec()
+  } else {
+    days
+      .iter()
+      .map(|&day| registry::find(year, day))
+      .collect::<anyhow::Result<Vec<_>>>()?
+  };
+
+  let days = solutions
+    .into_iter()
+    .map(|solution| {
+      let day = solution.day();
+      inputs
+        .load(year, day, source)
+        .and_then(|input| bench_day(solution, &input, runs))
+        .with_context(|| format!("failed to bench {year} day {day}"))
+    })
+    .collect::<anyhow::Result<Vec<_>>>()?;
+
+  Ok(Report { year, runs, days })
+}
+
+pub fn print_table(report: &Report) {
+  let fmt = |ns: u128| format!("{:.2?}", Duration::from_nanos(ns as u64));
+
+  println!("{} runs each", report.runs);
+  println!(
+    "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}",
+    "day", "stage", "min", "median", "max"
+  );
+  for day in &report.days {
+    for (stage, stats) in [
+      ("parse", day.parse),
+      ("part 1", day.part_1),
+      ("part 2", day.part_2),
+    ] {
+      println!(
+        "{:>3}  {stage:<6}  {:>10}  {:>10}  {:>10}",
+        day.day,
+        fmt(stats.min_ns),
+        fmt(stats.median_ns),
+        fmt(stats.max_ns)
+      );
+    }
+  }
+}
@@ -0,0 +1,349 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::input::{Inputs, Source};
+
+pub const USAGE: &str = "\
+usage: advent_of_code <year> <day> <part> [options]
+       advent_of_code --year <year> --day <day> --part <part> [options]
+       advent_of_code all <year> [--jobs <n>] [--inputs-dir <dir>]
+       advent_of_code verify <year> [--inputs-dir <dir>]
+       advent_of_code bench <year> [<day>...] [--runs <n>] [--json] [options]
+
+the input is read from `<inputs-dir>/<year>/day_<day>.txt` by default
+
+`all` runs every day and part of the year and prints the answers in a table,
+failing or panicking days are reported without stopping the run, with
+`--jobs` the days run in parallel but are still printed in order
+
+`verify` runs every solution of the year and compares the answers with
+`<inputs-dir>/<year>/answers.json`, which maps days to expected answers:
+  { \"1\": { \"part_1\": 142, \"part_2\": 281 } }
+
+`bench` runs the chosen days, or every day with an input, `--runs` times and
+reports parse and part times separately
+
+options:
+  -y, --year <year>         puzzle year, e.g. 2023
+  -d, --day <day>           puzzle day, 1 to 25
+  -p, --part <part>         puzzle part, 1 or 2
+  -i, --input <name>        use the named input `day_<day>.<name>.txt`, e.g. example1
+      --stdin               read the input from stdin
+      --inputs-dir <dir>    input directory, default `inputs`
+  -n, --runs <n>            bench: runs of each day, default 10
+      --json                bench: print results as json
+  -j, --jobs <n>            all: days run at once, 0 for one per cpu, default 1
+  -h, --help                print this message";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Selector {
+  pub year: u16,
+  pub day: u8,
+  pub part: u8,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Command {
+  Help,
+  Run {
+    selector: Selector,
+    source: Source,
+    inputs: Inputs,
+  },
+  All {
+    year: u16,
+    /// number of worker threads, at least 1
+    jobs: usize,
+    inputs: Inputs,
+  },
+  Verify {
+    year: u16,
+    inputs: Inputs,
+  },
+  Bench {
+    year: u16,
+    /// every day of the year when empty
+    days: Vec<u8>,
+    runs: usize,
+    json: bool,
+    source: Source,
+    inputs: Inputs,
+  },
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Command> {
+  let mut args = args.into_iter();
+
+  let mut positional = vec![];
+  let (mut year, mut day, mut part) = (None, None, None);
+  let (mut source, mut inputs) = (Source::default(), Inputs::default());
+  let (mut runs, mut json, mut jobs) = (None, false, None);
+
+  while let Some(arg) = args.next() {
+    if !arg.starts_with('-') {
+      positional.push(arg);
+      continue;
+    }
+
+    // accept both `--day 12` and `--day=12`
+    let (flag, value) = match arg.split_once('=') {
+      Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+      None => (arg, None),
+    };
+    let value = || {
+      value
+        .or_else(|| args.next())
+        .ok_or_else(|| anyhow!("missing value for `{flag}`"))
+    };
+
+    match flag.as_str() {
+      "-h" | "--help" => return Ok(Command::Help),
+      "-y" | "--year" => year = Some(value()?),
+      "-d" | "--day" => day = Some(value()?),
+      "-p" | "--part" => part = Some(value()?),
+      "-i" | "--input" => source = Source::Named(value()?),
+      "--stdin" => source = Source::Stdin,
+      "--inputs-dir" => inputs = Inputs::new(value()?),
+      "-n" | "--runs" => runs = Some(value()?),
+      "--json" => json = true,
+      "-j" | "--jobs" => jobs = Some(value()?),
+      _ => bail!("unknown option `{flag}`\n\n{USAGE}"),
+    }
+  }
+
+  if positional.first().is_some_and(|it| it == "bench") {
+    if part.is_some() {
+      bail!("`bench` runs both parts, part can't be chosen");
+    }
+    let mut positional = positional.into_iter().skip(1);
+    let year = merge("year", year, positional.next())?;
+    let days = day
+      .into_iter()
+      .chain(positional)
+      .map(|it| parse_day(&it))
+      .collect::<anyhow::Result<Vec<_>>>()?;
+    let runs = runs.map_or(Ok(10), |it| parse_num("runs", &it))?;
+    if runs == 0 {
+      bail!("runs must be at least 1");
+    }
+
+    return Ok(Command::Bench {
+      year: parse_num("year", &year)?,
+      days,
+      runs,
+      json,
+      source,
+      inputs,
+    });
+  }
+  if runs.is_some() || json {
+    bail!("`--runs` and `--json` are only supported by `bench`");
+  }
+  if jobs.is_some() && positional.first().is_none_or(|it| it != "all") {
+    bail!("`--jobs` is only supported by `all`");
+  }
+
+  if let Some(command @ ("all" | "verify")) = positional.first().map(String::as_str) {
+    if day.is_some() || part.is_some() || source != Source::Puzzle {
+      bail!(
+        "`{command}` runs every day with its puzzle input, day, part and input can't be chosen"
+      );
+    }
+    if positional.len() > 2 {
+      bail!("unexpected argument `{}`\n\n{USAGE}", positional[2]);
+    }
+    let year = parse_num("year", &merge("year", year, positional.get(1).cloned())?)?;
+    return Ok(match command {
+      "all" => Command::All {
+        year,
+        jobs: match jobs.map_or(Ok(1), |it| parse_num("jobs", &it))? {
+          0 => std::thread::available_parallelism().map_or(1, |it| it.get()),
+          jobs => jobs,
+        },
+        inputs,
+      },
+      _ => Command::Verify { year, inputs },
+    });
+  }
+
+  if positional.len() > 3 {
+    bail!("unexpected argument `{}`\n\n{USAGE}", positional[3]);
+  }
+  let mut positional = positional.into_iter();
+
+  let year = merge("year", year, positional.next())?;
+  let day = merge("day", day, positional.next())?;
+  let part = merge("part", part, positional.next())?;
+
+  let selector = Selector {
+    year: parse_num("year", &year)?,
+    day: parse_day(&day)?,
+    part: parse_num("part", &part)?,
+  };
+
+  if !(1..=2).contains(&selector.part) {
+    bail!("part must be 1 or 2, got {}", selector.part);
+  }
+
+  Ok(Command::Run {
+    selector,
+    source,
+    inputs,
+  })
+}
+
+fn merge(name: &str, flag: Option<String>, positional: Option<String>) -> anyhow::Result<String> {
+  match (flag, positional) {
+    (Some(_), Some(_)) => bail!("{name} given both as `--{name}` and as positional argument"),
+    (Some(it), None) | (None, Some(it)) => Ok(it),
+    (None, None) => bail!("missing {name}\n\n{USAGE}"),
+  }
+}
+
+fn parse_day(value: &str) -> anyhow::Result<u8> {
+  let day = parse_num("day", value)?;
+  if !(1..=25).contains(&day) {
+    bail!("day must be between 1 and 25, got {day}");
+  }
+  Ok(day)
+}
+
+fn parse_num<T>(name: &str, value: &str) -> anyhow::Result<T>
+where
+  T: FromStr,
+  T::Err: std::error::Error + Send + Sync + 'static,
+{
+  value
+    .parse::<T>()
+    .with_context(|| format!("invalid {name} `{value}`"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(args: &str) -> anyhow::Result<Command> {
+    parse_args(args.split_whitespace().map(str::to_string))
+  }
+
+  fn run(year: u16, day: u8, part: u8, source: Source) -> Command {
+    Command::Run {
+      selector: Selector { year, day, part },
+      source,
+      inputs: Inputs::default(),
+    }
+  }
+
+  #[test]
+  fn positionals_and_flags() {
+    let expected = run(2023, 8, 2, Source::Puzzle);
+    assert_eq!(parse("2023 8 2").unwrap(), expected);
+    assert_eq!(parse("--year 2023 --day 8 --part 2").unwrap(), expected);
+    assert_eq!(parse("-y=2023 -d 8 -p=2").unwrap(), expected);
+    assert_eq!(parse("--part=2 --day=8 --year=2023").unwrap(), expected);
+
+    assert_eq!(
+      parse("2023 8 1 -i example1").unwrap(),
+      run(2023, 8, 1, Source::Named("example1".to_string()))
+    );
+    assert_eq!(
+      parse("2023 8 1 --stdin --inputs-dir cache").unwrap(),
+      Command::Run {
+        selector: Selector {
+          year: 2023,
+          day: 8,
+          part: 1
+        },
+        source: Source::Stdin,
+        inputs: Inputs::new("cache"),
+      }
+    );
+    assert_eq!(parse("2023 8 2 --help").unwrap(), Command::Help);
+  }
+
+  #[test]
+  fn invalid_run() {
+    for args in [
+      "2023 8",
+      "2023 8 2 1",
+      "2023 0 1",
+      "2023 26 1",
+      "2023 8 3",
+      "2023 x 1",
+      "2023 8 1 --day 8",
+      "2023 8 1 --unknown",
+      "2023 8 --part",
+      "2023 8 1 --runs 3",
+      "2023 8 1 --jobs 2",
+    ] {
+      assert!(parse(args).is_err(), "{args}");
+    }
+  }
+
+  #[test]
+  fn all_and_verify() {
+    assert_eq!(
+      parse("all 2023 --jobs 4").unwrap(),
+      Command::All {
+        year: 2023,
+        jobs: 4,
+        inputs: Inputs::default()
+      }
+    );
+    assert!(
+      matches!(parse("all --year 2023 -j 0").unwrap(), Command::All { jobs, .. } if jobs >= 1)
+    );
+    assert_eq!(
+      parse("verify 2023").unwrap(),
+      Command::Verify {
+        year: 2023,
+        inputs: Inputs::default()
+      }
+    );
+
+    for args in [
+      "all 2023 8",
+      "all 2023 --day 8",
+      "all 2023 --stdin",
+      "verify 2023 --part 1",
+      "verify 2023 --jobs 2",
+      "verify",
+    ] {
+      assert!(parse(args).is_err(), "{args}");
+    }
+  }
+
+  #[test]
+  fn bench() {
+    assert_eq!(
+      parse("bench 2023 5 --day 8 -n 3 --json").unwrap(),
+      Command::Bench {
+        year: 2023,
+        days: vec![8, 5],
+        runs: 3,
+        json: true,
+        source: Source::Puzzle,
+        inputs: Inputs::default(),
+      }
+    );
+    assert!(matches!(
+      parse("bench 2023").unwrap(),
+      Command::Bench { days, runs: 10, .. } if days.is_empty()
+    ));
+
+    for args in [
+      "bench 2023 8 --part 1",
+      "bench 2023 --runs 0",
+      "bench 2023 30",
+      "bench",
+    ] {
+      assert!(parse(args).is_err(), "{args}");
+    }
+  }
+}
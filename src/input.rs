@@ -0,0 +1,84 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+pub const DEFAULT_DIR: &str = "inputs";
+
+/// where the input of a puzzle comes from
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub enum Source {
+  /// `<dir>/<year>/day_<day>.txt`
+  #[default]
+  Puzzle,
+  /// `<dir>/<year>/day_<day>.<name>.txt`, e.g. `example1`
+  Named(String),
+  Stdin,
+}
+
+/// input cache directory, laid out as `<dir>/<year>/day_<day>[.<name>].txt`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Inputs {
+  dir: PathBuf,
+}
+
+impl Default for Inputs {
+  fn default() -> Self {
+    Self::new(DEFAULT_DIR)
+  }
+}
+
+impl Inputs {
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self { dir: dir.into() }
+  }
+
+  pub fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  pub fn year_dir(&self, year: u16) -> PathBuf {
+    self.dir.join(year.to_string())
+  }
+
+  pub fn path(&self, year: u16, day: u8, name: Option<&str>) -> PathBuf {
+    let file = match name {
+      Some(name) => format!("day_{day}.{name}.txt"),
+      None => format!("day_{day}.txt"),
+    };
+    self.year_dir(year).join(file)
+  }
+
+  /// expected answers of the year, see [`crate::verify`]
+  pub fn answers_path(&self, year: u16) -> PathBuf {
+    self.year_dir(year).join("answers.json")
+  }
+
+  pub fn load(&self, year: u16, day: u8, source: &Source) -> anyhow::Result<String> {
+    let path = match source {
+      Source::Puzzle => self.path(year, day, None),
+      Source::Named(name) => self.path(year, day, Some(name)),
+      Source::Stdin => {
+        let mut input = String::new();
+        std::io::stdin()
+          .read_to_string(&mut input)
+          .context("failed to read input from stdin")?;
+        return Ok(input);
+      }
+    };
+
+    if !path.is_file() {
+      bail!(
+        "missing input {}, save the input there or pass `--stdin` to read it from stdin",
+        path.display()
+      );
+    }
+
+    std::fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))
+  }
+}
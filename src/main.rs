@@ -4,16 +4,62 @@
  */
 #![warn(missing_debug_implementations)]
 
-use std::io::Read;
+use anyhow::Context;
 
+use crate::cli::Command;
+
+pub mod answer;
+pub mod bench;
+pub mod cli;
+pub mod input;
+pub mod registry;
+pub mod run_all;
+pub mod solution;
 pub mod utils;
+pub mod verify;
 pub mod year_2023;
 
 fn main() -> anyhow::Result<()> {
-  let mut input = String::new();
-  std::io::stdin().read_to_string(&mut input)?;
+  let (selector, source, inputs) = match cli::parse_args(std::env::args().skip(1))? {
+    Command::Help => {
+      println!("{}", cli::USAGE);
+      return Ok(());
+    }
+    Command::Run {
+      selector,
+      source,
+      inputs,
+    } => (selector, source, inputs),
+    Command::All { year, jobs, inputs } => return run_all::run_all(&inputs, year, jobs),
+    Command::Verify { year, inputs } => return verify::verify(&inputs, year),
+    Command::Bench {
+      year,
+      days,
+      runs,
+      json,
+      source,
+      inputs,
+    } => {
+      let report = bench::bench(&inputs, &source, year, &days, runs)?;
+      if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+      } else {
+        bench::print_table(&report);
+      }
+      return Ok(());
+    }
+  };
+  let solution = registry::find(selector.year, selector.day)?;
+
+  let input = inputs.load(selector.year, selector.day, &source)?;
 
-  year_2023::day_15::part_2(input);
+  let parsed = solution.parse(&input).with_context(|| {
+    format!(
+      "failed to parse input of {} day {}",
+      selector.year, selector.day
+    )
+  })?;
+  println!("{}", parsed.part(selector.part)?);
 
   Ok(())
 }
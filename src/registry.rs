@@ -0,0 +1,39 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use anyhow::bail;
+use itertools::Itertools;
+
+use crate::solution::DynSolution;
+use crate::year_2023;
+
+/// solutions of a year, ordered by day
+pub type Solutions = &'static [&'static dyn DynSolution];
+
+pub const YEARS: &[(u16, Solutions)] = &[(2023, year_2023::SOLUTIONS)];
+
+pub fn year(year: u16) -> anyhow::Result<Solutions> {
+  let Some((_, solutions)) = YEARS.iter().find(|(it, _)| *it == year) else {
+    bail!(
+      "no solutions for year {year}, available years: {}",
+      YEARS.iter().map(|(it, _)| it).join(", ")
+    );
+  };
+
+  Ok(solutions)
+}
+
+pub fn find(year: u16, day: u8) -> anyhow::Result<&'static dyn DynSolution> {
+  let solutions = self::year(year)?;
+
+  let Some(solution) = solutions.iter().find(|it| it.day() == day) else {
+    bail!(
+      "day {day} of {year} is not implemented, available days: {}",
+      solutions.iter().map(|it| it.day()).join(", ")
+    );
+  };
+
+  Ok(*solution)
+}
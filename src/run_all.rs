@@ -0,0 +1,291 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+
+use crate::answer::Answer;
+use crate::input::{Inputs, Source};
+use crate::registry;
+use crate::solution::DynSolution;
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+  Answer(Answer),
+  Error(String),
+  Panic(String),
+  /// the input of the day is missing
+  Skip,
+}
+
+#[derive(Debug)]
+pub struct PartResult {
+  pub day: u8,
+  pub part: u8,
+  pub outcome: Outcome,
+  pub elapsed: Duration,
+}
+
+/// run `f`, turning an error or a panic into the matching outcome
+fn guard<T>(f: impl FnOnce() -> anyhow::Result<T>) -> Result<T, Outcome> {
+  match catch_unwind(AssertUnwindSafe(f)) {
+    Ok(Ok(it)) => Ok(it),
+    Ok(Err(err)) => Err(Outcome::Error(format!("{err:#}"))),
+    Err(payload) => Err(Outcome::Panic(panic_message(payload))),
+  }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+  payload
+    .downcast_ref::<&str>()
+    .map(|it| it.to_string())
+    .or_else(|| payload.downcast_ref::<String>().cloned())
+    .unwrap_or_else(|| "unknown panic payload".to_string())
+}
+
+/// run both parts of a day, never fails or panics, problems end up in the outcomes
+pub fn run_day(inputs: &Inputs, year: u16, solution: &dyn DynSolution) -> [PartResult; 2] {
+  let day = solution.day();
+  let result = |part, outcome, elapsed| PartResult {
+    day,
+    part,
+    outcome,
+    elapsed,
+  };
+
+  if !inputs.path(year, day, None).is_file() {
+    return [1, 2].map(|part| result(part, Outcome::Skip, Duration::ZERO));
+  }
+
+  let start = Instant::now();
+  let parsed = guard(|| {
+    let input = inputs.load(year, day, &Source::Puzzle)?;
+    solution.parse(&input)
+  });
+  let parse_elapsed = start.elapsed();
+
+  match parsed {
+    // a parse failure fails both parts
+    Err(outcome) => [
+      result(1, outcome.clone(), parse_elapsed),
+      result(2, outcome, parse_elapsed),
+    ],
+    Ok(parsed) => [1, 2].map(|part| {
+      let start = Instant::now();
+      let outcome = guard(|| parsed.part(part)).map_or_else(|it| it, Outcome::Answer);
+      result(part, outcome, start.elapsed())
+    }),
+  }
+}
+
+/// run the days on `jobs` worker threads, results are in the order of `solutions`
+pub fn run_days(
+  inputs: &Inputs,
+  year: u16,
+  solutions: &[&dyn DynSolution],
+  jobs: usize,
+) -> Vec<PartResult> {
+  if jobs <= 1 {
+    return solutions
+      .iter()
+      .flat_map(|solution| run_day(inputs, year, *solution))
+      .collect();
+  }
+
+  // workers take the next day from a shared counter and store the result in its slot
+  let next = AtomicUsize::new(0);
+  let slots = solutions
+    .iter()
+    .map(|_| Mutex::new(None))
+    .collect::<Vec<_>>();
+  std::thread::scope(|scope| {
+    for _ in 0..jobs.min(solutions.len()) {
+      scope.spawn(|| loop {
+        let idx = next.fetch_add(1, Ordering::Relaxed);
+        if idx >= solutions.len() {
+          break;
+        }
+        let result = run_day(inputs, year, solutions[idx]);
+        *slots[idx].lock().unwrap() = Some(result);
+      });
+    }
+  });
+
+  slots
+    .into_iter()
+    .flat_map(|it| {
+      it.into_inner()
+        .unwrap()
+        .expect("every day is run by a worker")
+    })
+    .collect()
+}
+
+/// run every day of `year` in day order, returns the results and the wall time
+pub fn run_year(
+  inputs: &Inputs,
+  year: u16,
+  jobs: usize,
+) -> anyhow::Result<(Vec<PartResult>, Duration)> {
+  let solutions = registry::year(year)?;
+
+  // panics are reported with the results instead of through the default hook
+  let hook = std::panic::take_hook();
+  std::panic::set_hook(Box::new(|_| {}));
+  let start = Instant::now();
+  let results = run_days(inputs, year, solutions, jobs);
+  let wall = start.elapsed();
+  std::panic::set_hook(hook);
+
+  Ok((results, wall))
+}
+
+/// run every day of `year`, print a table in day order, and error if any part failed
+pub fn run_all(inputs: &Inputs, year: u16, jobs: usize) -> anyhow::Result<()> {
+  let (results, wall) = run_year(inputs, year, jobs)?;
+  print_table(&results, wall)
+}
+
+pub fn print_table(results: &[PartResult], wall: Duration) -> anyhow::Result<()> {
+  println!("day  part  {:>10}  answer", "time");
+  for PartResult {
+    day,
+    part,
+    outcome,
+    elapsed,
+  } in results
+  {
+    let answer = match outcome {
+      Outcome::Answer(answer) => answer.to_string(),
+      Outcome::Error(err) => format!("ERROR {err}"),
+      Outcome::Panic(msg) => format!("PANIC {msg}"),
+      Outcome::Skip => "skip, missing input".to_string(),
+    };
+    let elapsed = format!("{elapsed:.2?}");
+    println!("{day:>3}  {part:>4}  {elapsed:>10}  {answer}");
+  }
+
+  let count = |f: fn(&Outcome) -> bool| results.iter().filter(|it| f(&it.outcome)).count();
+  let solved = count(|it| matches!(it, Outcome::Answer(_)));
+  let failed = count(|it| matches!(it, Outcome::Error(_) | Outcome::Panic(_)));
+  let skipped = count(|it| matches!(it, Outcome::Skip));
+  println!("\n{solved} solved, {failed} failed, {skipped} skipped in {wall:.2?}");
+
+  if failed > 0 {
+    bail!("{failed} of {} parts failed", solved + failed);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::*;
+  use crate::solution::Solution;
+
+  /// answers the number in its input in part 1, later days take less time, and panics on part 2
+  /// if the input says so
+  #[derive(Debug)]
+  struct Fake<const DAY: u8>;
+
+  impl<const DAY: u8> Solution for Fake<DAY> {
+    const YEAR: u16 = 1;
+    const DAY: u8 = DAY;
+    const TITLE: &'static str = "Fake";
+
+    type Input = String;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+      if input == "bad" {
+        bail!("bad input");
+      }
+      Ok(input.to_string())
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+      std::thread::sleep(Duration::from_millis(10 * (5 - DAY as u64)));
+      Ok(input.parse::<i64>()?.into())
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+      if input == "panic" {
+        panic!("told to panic");
+      }
+      Ok(input.len().into())
+    }
+  }
+
+  fn inputs(name: &str, days: &[(u8, &str)]) -> Inputs {
+    let dir = std::env::temp_dir().join(format!("aoc_{name}_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("1")).unwrap();
+    for (day, input) in days {
+      std::fs::write(dir.join("1").join(format!("day_{day}.txt")), input).unwrap();
+    }
+    Inputs::new(dir)
+  }
+
+  fn outcomes(results: &[PartResult]) -> Vec<(u8, u8, String)> {
+    results
+      .iter()
+      .map(|it| {
+        let outcome = match &it.outcome {
+          Outcome::Answer(answer) => answer.to_string(),
+          Outcome::Error(err) => format!("error {err}"),
+          Outcome::Panic(msg) => format!("panic {msg}"),
+          Outcome::Skip => "skip".to_string(),
+        };
+        (it.day, it.part, outcome)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn outcomes_in_day_order() {
+    let inputs = inputs("run_days", &[(1, "12"), (2, "bad"), (3, "panic")]);
+    let solutions: [&dyn DynSolution; 4] = [&Fake::<1>, &Fake::<2>, &Fake::<3>, &Fake::<4>];
+
+    let expected = [
+      (1, 1, "12"),
+      (1, 2, "2"),
+      (2, 1, "error bad input"),
+      (2, 2, "error bad input"),
+      (3, 1, "error invalid digit found in string"),
+      (3, 2, "panic told to panic"),
+      (4, 1, "skip"),
+      (4, 2, "skip"),
+    ]
+    .map(|(day, part, outcome)| (day, part, outcome.to_string()));
+    for jobs in [1, 2, 4] {
+      let results = run_days(&inputs, 1, &solutions, jobs);
+      assert_eq!(outcomes(&results), expected, "{jobs} jobs");
+    }
+
+    assert!(print_table(&run_days(&inputs, 1, &solutions, 2), Duration::ZERO).is_err());
+    std::fs::remove_dir_all(inputs.dir()).unwrap();
+  }
+
+  #[test]
+  fn panic_payloads() {
+    let message = |f: fn() -> anyhow::Result<()>| match guard(f) {
+      Err(Outcome::Panic(msg)) => msg,
+      _ => unreachable!(),
+    };
+    assert_eq!(message(|| panic!("static")), "static");
+    assert_eq!(message(|| panic!("formatted {}", 1)), "formatted 1");
+    assert_eq!(
+      message(|| std::panic::panic_any(1)),
+      "unknown panic payload"
+    );
+    assert!(
+      matches!(guard(|| bail!("failed") as anyhow::Result<()>), Err(Outcome::Error(it)) if it == "failed")
+    );
+  }
+}
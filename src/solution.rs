@@ -0,0 +1,93 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use anyhow::bail;
+
+use crate::answer::Answer;
+
+/// a puzzle of a day, the input is parsed once and shared by both parts
+pub trait Solution {
+  const YEAR: u16;
+  const DAY: u8;
+  const TITLE: &'static str;
+
+  type Input;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input>;
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer>;
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer>;
+}
+
+/// object safe form of [`Solution`], which is what the registry holds
+pub trait DynSolution: Sync {
+  fn year(&self) -> u16;
+
+  fn day(&self) -> u8;
+
+  fn title(&self) -> &'static str;
+
+  fn parse(&self, input: &str) -> anyhow::Result<Box<dyn Parsed>>;
+}
+
+/// parsed input of a [`DynSolution`]
+pub trait Parsed {
+  fn part_1(&self) -> anyhow::Result<Answer>;
+
+  fn part_2(&self) -> anyhow::Result<Answer>;
+
+  fn part(&self, part: u8) -> anyhow::Result<Answer> {
+    match part {
+      1 => self.part_1(),
+      2 => self.part_2(),
+      _ => bail!("part {part} does not exist"),
+    }
+  }
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+  fn part_1(&self) -> anyhow::Result<Answer> {
+    S::part_1(&self.0)
+  }
+
+  fn part_2(&self) -> anyhow::Result<Answer> {
+    S::part_2(&self.0)
+  }
+}
+
+impl<S> DynSolution for S
+where
+  S: Solution + Sync + 'static,
+{
+  fn year(&self) -> u16 {
+    S::YEAR
+  }
+
+  fn day(&self) -> u8 {
+    S::DAY
+  }
+
+  fn title(&self) -> &'static str {
+    S::TITLE
+  }
+
+  fn parse(&self, input: &str) -> anyhow::Result<Box<dyn Parsed>> {
+    Ok(Box::new(ParsedInput::<S>(S::parse(input)?)))
+  }
+}
+
+/// parse `input` and run one part, for tests against the puzzle examples
+#[cfg(test)]
+pub fn solve<S: Solution>(input: &str, part: u8) -> anyhow::Result<Answer> {
+  let input = S::parse(input)?;
+  match part {
+    1 => S::part_1(&input),
+    2 => S::part_2(&input),
+    _ => bail!("part {part} does not exist"),
+  }
+}
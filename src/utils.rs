@@ -8,6 +8,18 @@ use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::sync::{Arc, OnceLock};
 
+pub mod checked;
+pub mod cycle;
+pub mod graph;
+pub mod grid;
+pub mod interner;
+pub mod interval;
+pub mod memo;
+pub mod number_theory;
+pub mod parse;
+pub mod point;
+pub mod search;
+
 pub trait Then {
   fn then<R>(self, f: impl FnOnce(Self) -> R) -> R
   where
@@ -0,0 +1,120 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::any::type_name;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use num::{CheckedAdd, CheckedMul, CheckedSub, FromPrimitive, Integer, ToPrimitive};
+
+/// integers the overflow prone solutions can run with, primitives report overflow as an error
+/// instead of wrapping, and `BigInt` never overflows
+pub trait Int:
+  Integer
+  + CheckedAdd
+  + CheckedSub
+  + CheckedMul
+  + FromPrimitive
+  + FromStr<Err: Display>
+  + Clone
+  + Debug
+  + Display
+{
+}
+
+impl<T> Int for T where
+  T: Integer
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + FromPrimitive
+    + FromStr<Err: Display>
+    + Clone
+    + Debug
+    + Display
+{
+}
+
+pub fn add<T: Int>(a: &T, b: &T) -> anyhow::Result<T> {
+  a.checked_add(b)
+    .ok_or_else(|| anyhow!("{a} + {b} overflows {}", type_name::<T>()))
+}
+
+pub fn sub<T: Int>(a: &T, b: &T) -> anyhow::Result<T> {
+  a.checked_sub(b)
+    .ok_or_else(|| anyhow!("{a} - {b} overflows {}", type_name::<T>()))
+}
+
+pub fn mul<T: Int>(a: &T, b: &T) -> anyhow::Result<T> {
+  a.checked_mul(b)
+    .ok_or_else(|| anyhow!("{a} * {b} overflows {}", type_name::<T>()))
+}
+
+pub fn sum<T: Int>(values: impl IntoIterator<Item = T>) -> anyhow::Result<T> {
+  values
+    .into_iter()
+    .try_fold(T::zero(), |acc, it| add(&acc, &it))
+}
+
+/// `a - b` if `a` is the larger one, otherwise `b - a`
+pub fn abs_diff<T: Int>(a: &T, b: &T) -> T {
+  if a >= b {
+    a.clone() - b.clone()
+  } else {
+    b.clone() - a.clone()
+  }
+}
+
+/// parse `text` as `T`, fails if it doesn't fit
+pub fn parse<T: Int>(text: &str) -> anyhow::Result<T> {
+  text
+    .parse()
+    .map_err(|err| anyhow!("{text:?} as {}: {err}", type_name::<T>()))
+}
+
+/// `value` as `T`, if it fits
+pub fn cast<T: Int>(value: impl ToPrimitive + Display) -> anyhow::Result<T> {
+  value
+    .to_i128()
+    .and_then(T::from_i128)
+    .or_else(|| value.to_u128().and_then(T::from_u128))
+    .ok_or_else(|| anyhow!("{value} doesn't fit in {}", type_name::<T>()))
+}
+
+#[cfg(test)]
+mod tests {
+  use num::BigInt;
+
+  use super::*;
+
+  #[test]
+  fn overflow_is_an_error() {
+    assert_eq!(add(&200_u8, &55).unwrap(), 255);
+    assert_eq!(
+      add(&200_u8, &56).unwrap_err().to_string(),
+      "200 + 56 overflows u8"
+    );
+    assert!(sub(&1_u32, &2).is_err());
+    assert!(mul(&i64::MAX, &2).is_err());
+    assert!(sum([100_u8, 100, 100]).is_err());
+    assert!(cast::<u8>(256).is_err());
+    assert_eq!(cast::<i8>(-128_i64).unwrap(), -128);
+    assert_eq!(abs_diff(&3_u8, &10), 7);
+    assert_eq!(parse::<u8>("255").unwrap(), 255);
+    assert!(parse::<u8>("256").is_err());
+  }
+
+  #[test]
+  fn big_ints_dont_overflow() {
+    let max = cast::<BigInt>(u64::MAX).unwrap();
+    let square = mul(&max, &max).unwrap();
+    assert_eq!(
+      square.to_string(),
+      "340282366920938463426481119284349108225"
+    );
+    assert_eq!(sum([square.clone(), square.clone()]).unwrap(), square * 2);
+  }
+}
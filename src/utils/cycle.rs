@@ -0,0 +1,182 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// shape of the sequence `start, step(start), step(step(start)), ..`, which enters a loop after
+/// `tail` steps and repeats every `period` steps from there
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Cycle {
+  pub tail: usize,
+  pub period: usize,
+}
+
+impl Cycle {
+  /// the smallest step which has the same state as step `n`
+  pub fn reduce(&self, n: usize) -> usize {
+    if n < self.tail {
+      n
+    } else {
+      self.tail + (n - self.tail) % self.period
+    }
+  }
+}
+
+fn advance<S>(mut state: S, step: &mut impl FnMut(&S) -> S, n: usize) -> S {
+  for _ in 0..n {
+    state = step(&state);
+  }
+  state
+}
+
+/// Floyd's tortoise and hare, keeps only two states but steps about three times the sequence
+///
+/// the sequence must loop eventually, otherwise this never returns
+pub fn floyd<S: Eq + Clone>(start: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+  let mut slow = step(start);
+  let mut fast = step(&slow);
+  while slow != fast {
+    slow = step(&slow);
+    fast = advance(fast, &mut step, 2);
+  }
+
+  // the distance from start to the loop equals the distance from the meeting point to the loop
+  let mut tail = 0;
+  slow = start.clone();
+  while slow != fast {
+    slow = step(&slow);
+    fast = step(&fast);
+    tail += 1;
+  }
+
+  let mut period = 1;
+  fast = step(&slow);
+  while slow != fast {
+    fast = step(&fast);
+    period += 1;
+  }
+
+  Cycle { tail, period }
+}
+
+/// Brent's algorithm, keeps only two states and steps less than [`floyd`]
+///
+/// the sequence must loop eventually, otherwise this never returns
+pub fn brent<S: Eq + Clone>(start: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+  let mut power = 1;
+  let mut period = 1;
+  let mut slow = start.clone();
+  let mut fast = step(start);
+  while slow != fast {
+    if power == period {
+      slow = fast.clone();
+      power *= 2;
+      period = 0;
+    }
+    fast = step(&fast);
+    period += 1;
+  }
+
+  // walk two states `period` apart until they meet at the start of the loop
+  let mut tail = 0;
+  slow = start.clone();
+  fast = advance(start.clone(), &mut step, period);
+  while slow != fast {
+    slow = step(&slow);
+    fast = step(&fast);
+    tail += 1;
+  }
+
+  Cycle { tail, period }
+}
+
+/// remember every state until one repeats, steps the least but keeps the whole sequence
+///
+/// returns the states of the tail and one period in order, so any step can be looked up
+pub fn hashed<S: Eq + Hash + Clone>(start: &S, mut step: impl FnMut(&S) -> S) -> (Cycle, Vec<S>) {
+  let mut seen = HashMap::new();
+  let mut states = vec![];
+  let mut state = start.clone();
+  loop {
+    if let Some(&tail) = seen.get(&state) {
+      let period = states.len() - tail;
+      return (Cycle { tail, period }, states);
+    }
+    let next = step(&state);
+    seen.insert(state.clone(), states.len());
+    states.push(state);
+    state = next;
+  }
+}
+
+/// the state after `n` steps, skipping whole periods once the loop is found
+pub fn nth<S: Eq + Hash + Clone>(start: &S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+  let mut seen = HashMap::new();
+  let mut states = vec![];
+  let mut state = start.clone();
+  for idx in 0..n {
+    if let Some(&tail) = seen.get(&state) {
+      let cycle = Cycle {
+        tail,
+        period: idx - tail,
+      };
+      return states.swap_remove(cycle.reduce(n));
+    }
+    let next = step(&state);
+    seen.insert(state.clone(), idx);
+    states.push(state);
+    state = next;
+  }
+  state
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 3 -> 4 -> 5 -> 6 -> 7 -> 8 -> 4 -> ..
+  fn step(it: &u32) -> u32 {
+    if *it == 8 {
+      4
+    } else {
+      it + 1
+    }
+  }
+
+  #[test]
+  fn finds_tail_and_period() {
+    let expected = Cycle { tail: 1, period: 5 };
+    assert_eq!(floyd(&3, step), expected);
+    assert_eq!(brent(&3, step), expected);
+    assert_eq!(hashed(&3, step), (expected, vec![3, 4, 5, 6, 7, 8]));
+  }
+
+  #[test]
+  fn pure_loop_has_no_tail() {
+    let expected = Cycle { tail: 0, period: 5 };
+    assert_eq!(floyd(&4, step), expected);
+    assert_eq!(brent(&4, step), expected);
+    assert_eq!(hashed(&4, step).0, expected);
+    assert_eq!(brent(&1, |_| 1), Cycle { tail: 0, period: 1 });
+  }
+
+  #[test]
+  fn nth_matches_stepping() {
+    let step = |it: &u64| (it * it + 1) % 1009;
+    let mut state = 2;
+    for n in 0..3000 {
+      assert_eq!(nth(&2, step, n), state, "step {n}");
+      state = step(&state);
+    }
+
+    let cycle = brent(&2, step);
+    let states = hashed(&2, step).1;
+    assert_eq!(
+      nth(&2, step, 1_000_000_000),
+      states[cycle.reduce(1_000_000_000)]
+    );
+  }
+}
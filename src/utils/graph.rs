@@ -0,0 +1,127 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use anyhow::{anyhow, bail};
+
+use crate::utils::interner::Interner;
+
+/// directed graph of labelled nodes, the successors of each node keep their order
+///
+/// nodes are `u32` indices in order of definition, edges are stored flat so building and
+/// looking up successors doesn't need any hashing after the labels are resolved
+#[derive(Debug, Clone, Default)]
+pub struct DiGraph {
+  labels: Interner,
+  /// successors of node `n` are `targets[starts[n]..starts[n + 1]]`
+  starts: Vec<u32>,
+  targets: Vec<u32>,
+}
+
+impl DiGraph {
+  /// build from each node's label and the labels of its successors, every successor must be
+  /// defined as a node as well
+  pub fn new<'a, S>(nodes: impl IntoIterator<Item = (&'a str, S)>) -> anyhow::Result<Self>
+  where
+    S: IntoIterator<Item = &'a str>,
+  {
+    let nodes = nodes.into_iter().collect::<Vec<_>>();
+
+    let mut labels = Interner::new();
+    for (label, _) in &nodes {
+      if labels.get(label).is_some() {
+        bail!("node {label:?} is defined twice");
+      }
+      labels.intern(label);
+    }
+
+    let mut starts = vec![0];
+    let mut targets = vec![];
+    for (label, successors) in nodes {
+      for successor in successors {
+        let idx = labels
+          .get(successor)
+          .ok_or_else(|| anyhow!("node {label:?} leads to unknown node {successor:?}"))?;
+        targets.push(idx);
+      }
+      starts.push(targets.len() as u32);
+    }
+
+    Ok(Self {
+      labels,
+      starts,
+      targets,
+    })
+  }
+
+  pub fn len(&self) -> usize {
+    self.labels.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.labels.is_empty()
+  }
+
+  pub fn node(&self, label: &str) -> Option<u32> {
+    self.labels.get(label)
+  }
+
+  pub fn label(&self, node: u32) -> &str {
+    self.labels.label(node)
+  }
+
+  /// every node with its label, in order of definition
+  pub fn nodes(&self) -> impl Iterator<Item = (u32, &str)> + '_ {
+    (0..).zip(self.labels.labels().iter().map(String::as_str))
+  }
+
+  pub fn successors(&self, node: u32) -> &[u32] {
+    let node = node as usize;
+    &self.targets[self.starts[node] as usize..self.starts[node + 1] as usize]
+  }
+
+  pub fn edge_count(&self) -> usize {
+    self.targets.len()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::search;
+
+  fn graph() -> DiGraph {
+    DiGraph::new([
+      ("a", vec!["b", "c"]),
+      ("b", vec!["d"]),
+      ("c", vec!["d", "a"]),
+      ("d", vec![]),
+      ("e", vec!["e"]),
+    ])
+    .unwrap()
+  }
+
+  #[test]
+  fn nodes_and_successors() {
+    let graph = graph();
+    assert_eq!(graph.len(), 5);
+    assert_eq!(graph.edge_count(), 6);
+    assert_eq!(graph.node("c"), Some(2));
+    assert_eq!(graph.label(3), "d");
+    assert_eq!(graph.successors(2), [3, 0]);
+    assert!(graph.successors(3).is_empty());
+    assert_eq!(graph.nodes().nth(4), Some((4, "e")));
+
+    let search = search::bfs([0], |it| graph.successors(*it).to_vec());
+    assert_eq!(search.distance(&3), Some(2));
+    assert!(!search.contains(&4));
+  }
+
+  #[test]
+  fn rejects_unknown_and_duplicate_nodes() {
+    let err = DiGraph::new([("a", vec!["b"])]).unwrap_err();
+    assert_eq!(err.to_string(), "node \"a\" leads to unknown node \"b\"");
+    assert!(DiGraph::new([("a", vec![]), ("a", vec![])]).is_err());
+  }
+}
@@ -0,0 +1,364 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+use anyhow::bail;
+
+use crate::utils::parse;
+use crate::utils::point::Direction;
+
+/// offsets of the 4 orthogonal neighbours, clockwise from up
+const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+/// offsets of the 8 surrounding cells, clockwise from up
+const OFFSETS_8: [(isize, isize); 8] = [
+  (0, -1),
+  (1, -1),
+  (1, 0),
+  (1, 1),
+  (0, 1),
+  (-1, 1),
+  (-1, 0),
+  (-1, -1),
+];
+
+/// rectangular grid stored row by row, positions are `(x, y)` with `(0, 0)` at the top left
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+  width: usize,
+  height: usize,
+  cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+  pub fn from_rows(rows: Vec<Vec<T>>) -> anyhow::Result<Self> {
+    let width = rows.first().map_or(0, Vec::len);
+    if let Some((y, row)) = rows.iter().enumerate().find(|(_, it)| it.len() != width) {
+      bail!("row {y} has {} cells, expected {width}", row.len());
+    }
+
+    Ok(Self {
+      width,
+      height: rows.len(),
+      cells: rows.into_iter().flatten().collect(),
+    })
+  }
+
+  /// parse a char map, one row per line, each char converted by `T::try_from`
+  pub fn parse(input: &str) -> anyhow::Result<Self>
+  where
+    T: TryFrom<char>,
+    T::Error: Into<anyhow::Error>,
+  {
+    let mut width = None;
+    let mut height = 0;
+    let mut cells = vec![];
+    for line in parse::lines(input) {
+      let row = line.parse_chars(line.text(), |it| T::try_from(it).map_err(Into::into))?;
+      match width {
+        None => width = Some(row.len()),
+        Some(width) if width != row.len() => {
+          return Err(line.error(format!("row has {} cells, expected {width}", row.len())));
+        }
+        Some(_) => {}
+      }
+      height += 1;
+      cells.extend(row);
+    }
+
+    Ok(Self {
+      width: width.unwrap_or(0),
+      height,
+      cells,
+    })
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+    x < self.width && y < self.height
+  }
+
+  pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+    self
+      .contains(pos)
+      .then(|| &self.cells[pos.1 * self.width + pos.0])
+  }
+
+  pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+    self
+      .contains(pos)
+      .then(|| &mut self.cells[pos.1 * self.width + pos.0])
+  }
+
+  /// `pos` moved by `offset`, if it is still inside the grid
+  pub fn offset(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+    let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+    self.contains(pos).then_some(pos)
+  }
+
+  /// one step from `pos` towards `dir`, if it is still inside the grid
+  pub fn step(&self, pos: (usize, usize), dir: Direction) -> Option<(usize, usize)> {
+    self.offset(pos, dir.offset().into())
+  }
+
+  /// orthogonal neighbours of `pos` inside the grid
+  pub fn neighbours_4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+    OFFSETS_4
+      .into_iter()
+      .filter_map(move |it| self.offset(pos, it))
+  }
+
+  /// orthogonal and diagonal neighbours of `pos` inside the grid
+  pub fn neighbours_8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+    OFFSETS_8
+      .into_iter()
+      .filter_map(move |it| self.offset(pos, it))
+  }
+
+  pub fn row(&self, y: usize) -> &[T] {
+    &self.cells[y * self.width..(y + 1) * self.width]
+  }
+
+  pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+    (0..self.height).map(|y| self.row(y))
+  }
+
+  pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+    assert!(
+      x < self.width,
+      "column {x} out of grid of width {}",
+      self.width
+    );
+    self.cells[x..].iter().step_by(self.width)
+  }
+
+  pub fn columns(
+    &self,
+  ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &T>> + ExactSizeIterator {
+    (0..self.width).map(|x| self.column(x))
+  }
+
+  /// positions in row order
+  pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+    let width = self.width;
+    (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+  }
+
+  /// cells with their positions in row order
+  pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+    self.positions().zip(&self.cells)
+  }
+
+  pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+    Grid {
+      width: self.width,
+      height: self.height,
+      cells: self.cells.iter().map(f).collect(),
+    }
+  }
+
+  /// rebuild a `width` x `height` grid where each cell is taken from `self` at `from(x, y)`
+  fn remap(
+    &self,
+    width: usize,
+    height: usize,
+    from: impl Fn(usize, usize) -> (usize, usize),
+  ) -> Self
+  where
+    T: Clone,
+  {
+    let cells = (0..height)
+      .flat_map(|y| (0..width).map(move |x| (x, y)))
+      .map(|(x, y)| self[from(x, y)].clone())
+      .collect();
+    Self {
+      width,
+      height,
+      cells,
+    }
+  }
+
+  /// mirror along the main diagonal, rows become columns
+  pub fn transpose(&self) -> Self
+  where
+    T: Clone,
+  {
+    self.remap(self.height, self.width, |x, y| (y, x))
+  }
+
+  /// rotate by 90 degrees clockwise
+  pub fn rotate_right(&self) -> Self
+  where
+    T: Clone,
+  {
+    let height = self.height;
+    self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+  }
+
+  /// rotate by 90 degrees counterclockwise
+  pub fn rotate_left(&self) -> Self
+  where
+    T: Clone,
+  {
+    let width = self.width;
+    self.remap(self.height, self.width, |x, y| (width - 1 - y, x))
+  }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, pos: (usize, usize)) -> &T {
+    self.get(pos).unwrap_or_else(|| {
+      panic!(
+        "position {pos:?} out of grid of size {}x{}",
+        self.width, self.height
+      )
+    })
+  }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+  fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+    let (width, height) = (self.width, self.height);
+    self
+      .get_mut(pos)
+      .unwrap_or_else(|| panic!("position {pos:?} out of grid of size {width}x{height}"))
+  }
+}
+
+/// writes each cell with its own `Display`, one line per row
+impl<T: Display> Display for Grid<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    for (y, row) in self.rows().enumerate() {
+      if y > 0 {
+        writeln!(f)?;
+      }
+      for cell in row {
+        write!(f, "{cell}")?;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+  enum Cell {
+    Wall,
+    Open,
+  }
+
+  impl TryFrom<char> for Cell {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> anyhow::Result<Self> {
+      Ok(match value {
+        '#' => Cell::Wall,
+        '.' => Cell::Open,
+        _ => bail!("unexpected char {value:?} in map"),
+      })
+    }
+  }
+
+  impl Display for Cell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      let char = match self {
+        Cell::Wall => '#',
+        Cell::Open => '.',
+      };
+      write!(f, "{char}")
+    }
+  }
+
+  const MAP: &str = "\
+#..
+.#.";
+
+  #[test]
+  fn parse_and_display() {
+    let grid = Grid::<Cell>::parse(MAP).unwrap();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(1, 1)], Cell::Wall);
+    assert_eq!(grid.to_string(), MAP);
+  }
+
+  #[test]
+  fn parse_errors() {
+    let err = Grid::<Cell>::parse("#..\n.x.").unwrap_err();
+    assert_eq!(err.to_string(), "line 2, col 2: unexpected char 'x' in map");
+
+    let err = Grid::<Cell>::parse("#..\n.#").unwrap_err();
+    assert_eq!(err.to_string(), "line 2: row has 2 cells, expected 3");
+  }
+
+  #[test]
+  fn get_is_bounds_checked() {
+    let grid = Grid::<Cell>::parse(MAP).unwrap();
+    assert_eq!(grid.get((2, 1)), Some(&Cell::Open));
+    assert_eq!(grid.get((3, 0)), None);
+    assert_eq!(grid.get((0, 2)), None);
+    assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+    assert_eq!(grid.offset((0, 0), (1, 1)), Some((1, 1)));
+    assert_eq!(grid.step((0, 0), Direction::Up), None);
+    assert_eq!(grid.step((0, 0), Direction::Down), Some((0, 1)));
+  }
+
+  #[test]
+  fn neighbours() {
+    let grid = Grid::<Cell>::parse(MAP).unwrap();
+    assert_eq!(
+      grid.neighbours_4((0, 0)).collect::<Vec<_>>(),
+      [(1, 0), (0, 1)]
+    );
+    assert_eq!(
+      grid.neighbours_8((1, 0)).collect::<Vec<_>>(),
+      [(2, 0), (2, 1), (1, 1), (0, 1), (0, 0)]
+    );
+  }
+
+  #[test]
+  fn rows_and_columns() {
+    let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    assert_eq!(grid.row(1), [4, 5, 6]);
+    assert_eq!(grid.rows().count(), 2);
+    assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [3, 6]);
+    assert_eq!(
+      grid.columns().map(|it| it.sum::<i32>()).collect::<Vec<_>>(),
+      [5, 7, 9]
+    );
+    assert!(Grid::from_rows(vec![vec![1], vec![]]).is_err());
+  }
+
+  #[test]
+  fn transpose_and_rotate() {
+    let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    let rows = |grid: &Grid<i32>| grid.rows().map(<[_]>::to_vec).collect::<Vec<_>>();
+
+    assert_eq!(rows(&grid.transpose()), [[1, 4], [2, 5], [3, 6]]);
+    assert_eq!(rows(&grid.rotate_right()), [[4, 1], [5, 2], [6, 3]]);
+    assert_eq!(rows(&grid.rotate_left()), [[3, 6], [2, 5], [1, 4]]);
+    assert_eq!(grid.rotate_right().rotate_left(), grid);
+    assert_eq!(
+      grid
+        .rotate_right()
+        .rotate_right()
+        .rotate_right()
+        .rotate_right(),
+      grid
+    );
+  }
+}
@@ -0,0 +1,80 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::collections::HashMap;
+
+/// table of labels to dense `u32` indices, given out in order of first appearance
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+  indices: HashMap<String, u32>,
+  labels: Vec<String>,
+}
+
+impl Interner {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// index of `label`, added to the table if it's new
+  pub fn intern(&mut self, label: &str) -> u32 {
+    if let Some(&idx) = self.indices.get(label) {
+      return idx;
+    }
+
+    let idx = u32::try_from(self.labels.len()).expect("more than u32::MAX labels");
+    self.indices.insert(label.to_string(), idx);
+    self.labels.push(label.to_string());
+    idx
+  }
+
+  pub fn get(&self, label: &str) -> Option<u32> {
+    self.indices.get(label).copied()
+  }
+
+  /// label of `idx`, panics if it was never given out
+  pub fn label(&self, idx: u32) -> &str {
+    &self.labels[idx as usize]
+  }
+
+  pub fn labels(&self) -> &[String] {
+    &self.labels
+  }
+
+  pub fn len(&self) -> usize {
+    self.labels.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.labels.is_empty()
+  }
+}
+
+impl<'a> FromIterator<&'a str> for Interner {
+  fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+    let mut interner = Self::new();
+    iter.into_iter().for_each(|it| {
+      interner.intern(it);
+    });
+    interner
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn indices_in_order_of_appearance() {
+    let mut interner = Interner::from_iter(["AAA", "BBB", "AAA"]);
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.get("BBB"), Some(1));
+    assert_eq!(interner.get("CCC"), None);
+
+    assert_eq!(interner.intern("CCC"), 2);
+    assert_eq!(interner.intern("AAA"), 0);
+    assert_eq!(interner.label(2), "CCC");
+    assert_eq!(interner.labels(), ["AAA", "BBB", "CCC"]);
+  }
+}
@@ -0,0 +1,383 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::ops::{Add, Range, Sub};
+
+use anyhow::{anyhow, bail};
+use num::CheckedAdd;
+
+/// set of values stored as sorted, disjoint and non-adjacent half open ranges
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct IntervalSet<T> {
+  ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+  fn default() -> Self {
+    Self { ranges: vec![] }
+  }
+}
+
+impl<T: Clone + Ord> IntervalSet<T> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn ranges(&self) -> &[Range<T>] {
+    &self.ranges
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ranges.is_empty()
+  }
+
+  pub fn min(&self) -> Option<T> {
+    self.ranges.first().map(|it| it.start.clone())
+  }
+
+  pub fn contains(&self, value: &T) -> bool {
+    let idx = self.ranges.partition_point(|it| it.end <= *value);
+    self.ranges.get(idx).is_some_and(|it| it.contains(value))
+  }
+
+  pub fn insert(&mut self, range: Range<T>) {
+    if range.is_empty() {
+      return;
+    }
+
+    // ranges touching or overlapping `range` are merged into it
+    let from = self.ranges.partition_point(|it| it.end < range.start);
+    let to = self.ranges.partition_point(|it| it.start <= range.end);
+    let merged = if from < to {
+      let start = range.start.min(self.ranges[from].start.clone());
+      start..range.end.max(self.ranges[to - 1].end.clone())
+    } else {
+      range
+    };
+    self.ranges.splice(from..to, [merged]);
+  }
+
+  pub fn union(&self, other: &Self) -> Self {
+    let mut result = self.clone();
+    other.ranges.iter().for_each(|it| result.insert(it.clone()));
+    result
+  }
+
+  pub fn intersection(&self, other: &Self) -> Self {
+    let mut ranges = vec![];
+    let (mut a, mut b) = (
+      self.ranges.iter().peekable(),
+      other.ranges.iter().peekable(),
+    );
+    while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+      let overlap = x.start.clone().max(y.start.clone())..x.end.clone().min(y.end.clone());
+      if !overlap.is_empty() {
+        ranges.push(overlap);
+      }
+      if x.end < y.end {
+        a.next();
+      } else {
+        b.next();
+      }
+    }
+    Self { ranges }
+  }
+
+  pub fn difference(&self, other: &Self) -> Self {
+    let mut ranges = vec![];
+    for range in &self.ranges {
+      let mut start = range.start.clone();
+      let from = other.ranges.partition_point(|it| it.end <= range.start);
+      for cut in other.ranges[from..]
+        .iter()
+        .take_while(|it| it.start < range.end)
+      {
+        if start < cut.start {
+          ranges.push(start.clone()..cut.start.clone());
+        }
+        start = start.max(cut.end.clone());
+      }
+      if start < range.end {
+        ranges.push(start..range.end.clone());
+      }
+    }
+    Self { ranges }
+  }
+}
+
+impl<T: Clone + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+  fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+    let mut set = Self::new();
+    iter.into_iter().for_each(|it| set.insert(it));
+    set
+  }
+}
+
+/// piecewise map where each piece shifts a source range onto a destination starting at `dst`,
+/// values outside every piece map to themselves
+///
+/// `new` checks that the end of every destination fits in `T`, every value computed later lies
+/// inside a source or a destination, so the plain `+` and `-` below can't overflow
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RangeMap<T> {
+  /// sorted by source and disjoint
+  pieces: Vec<(Range<T>, T)>,
+}
+
+impl<T> Default for RangeMap<T> {
+  fn default() -> Self {
+    Self { pieces: vec![] }
+  }
+}
+
+impl<T> RangeMap<T>
+where
+  T: Clone + Ord + Add<Output = T> + Sub<Output = T> + CheckedAdd,
+{
+  pub fn identity() -> Self {
+    Self::default()
+  }
+
+  /// build from `(source, destination start)` pairs, the sources must not overlap and the
+  /// destinations must end inside `T`
+  pub fn new(pieces: impl IntoIterator<Item = (Range<T>, T)>) -> anyhow::Result<Self> {
+    let mut pieces = pieces
+      .into_iter()
+      .filter(|it| !it.0.is_empty())
+      .collect::<Vec<_>>();
+    pieces.sort_by(|a, b| a.0.start.cmp(&b.0.start));
+    if pieces.windows(2).any(|it| it[0].0.end > it[1].0.start) {
+      bail!("source ranges of a map overlap");
+    }
+    for (range, dst) in &pieces {
+      dst
+        .checked_add(&(range.end.clone() - range.start.clone()))
+        .ok_or_else(|| anyhow!("destination of a map is out of range"))?;
+    }
+    Ok(Self::normalized(pieces))
+  }
+
+  /// drop identity pieces and merge continuous neighbours, `pieces` must be sorted and disjoint
+  fn normalized(pieces: Vec<(Range<T>, T)>) -> Self {
+    let mut result: Vec<(Range<T>, T)> = vec![];
+    for (range, dst) in pieces {
+      if range.start == dst {
+        continue;
+      }
+      match result.last_mut() {
+        Some((last, last_dst))
+          if last.end == range.start && shift(last_dst, &last.start, &last.end) == dst =>
+        {
+          last.end = range.end;
+        }
+        _ => result.push((range, dst)),
+      }
+    }
+    Self { pieces: result }
+  }
+
+  pub fn pieces(&self) -> &[(Range<T>, T)] {
+    &self.pieces
+  }
+
+  pub fn get(&self, value: T) -> T {
+    let idx = self.pieces.partition_point(|it| it.0.end <= value);
+    match self.pieces.get(idx) {
+      Some((range, dst)) if range.contains(&value) => shift(dst, &range.start, &value),
+      _ => value,
+    }
+  }
+
+  /// split `range` along the pieces, identity gaps included, as `(source, destination start)`
+  pub fn segments(&self, range: Range<T>) -> Vec<(Range<T>, T)> {
+    let mut segments = vec![];
+    let mut start = range.start.clone();
+    let from = self.pieces.partition_point(|it| it.0.end <= range.start);
+    for (piece, dst) in self.pieces[from..]
+      .iter()
+      .take_while(|it| it.0.start < range.end)
+    {
+      if start < piece.start {
+        segments.push((start.clone()..piece.start.clone(), start.clone()));
+      }
+      let overlap = start.max(piece.start.clone())..range.end.clone().min(piece.end.clone());
+      let overlap_dst = shift(dst, &piece.start, &overlap.start);
+      start = overlap.end.clone();
+      segments.push((overlap, overlap_dst));
+    }
+    if start < range.end {
+      segments.push((start.clone()..range.end, start));
+    }
+    segments
+  }
+
+  /// image of a whole set
+  pub fn apply(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+    set
+      .ranges()
+      .iter()
+      .flat_map(|range| self.segments(range.clone()))
+      .map(|(src, dst)| dst.clone()..shift(&dst, &src.start, &src.end))
+      .collect()
+  }
+
+  /// the map applying `self` first and `next` after
+  pub fn compose(&self, next: &Self) -> Self {
+    let mut pieces = vec![];
+
+    // through a piece of `self`, then along the pieces of `next`
+    for (range, dst) in &self.pieces {
+      let image = dst.clone()..shift(dst, &range.start, &range.end);
+      for (src, next_dst) in next.segments(image) {
+        let start = shift(&range.start, dst, &src.start);
+        let end = shift(&range.start, dst, &src.end);
+        pieces.push((start..end, next_dst));
+      }
+    }
+
+    // where `self` is the identity only `next` applies
+    let domain = self.domain();
+    for (range, dst) in &next.pieces {
+      let uncovered = IntervalSet::from_iter([range.clone()]).difference(&domain);
+      for src in uncovered.ranges() {
+        pieces.push((src.clone(), shift(dst, &range.start, &src.start)));
+      }
+    }
+
+    pieces.sort_by(|a, b| a.0.start.cmp(&b.0.start));
+    Self::normalized(pieces)
+  }
+
+  /// values which are moved by the map
+  pub fn domain(&self) -> IntervalSet<T> {
+    self.pieces.iter().map(|it| it.0.clone()).collect()
+  }
+
+  /// where the values of the moved ranges end up
+  pub fn image(&self) -> IntervalSet<T> {
+    self
+      .pieces
+      .iter()
+      .map(|(range, dst)| dst.clone()..shift(dst, &range.start, &range.end))
+      .collect()
+  }
+
+  /// sorted values where a piece starts or ends, between two of them the map is one shift
+  pub fn breakpoints(&self) -> Vec<T> {
+    let mut points = self
+      .pieces
+      .iter()
+      .flat_map(|it| [it.0.start.clone(), it.0.end.clone()])
+      .collect::<Vec<_>>();
+    points.dedup();
+    points
+  }
+
+  /// the map going back from destinations to sources, only exists if the map is a bijection
+  pub fn invert(&self) -> anyhow::Result<Self> {
+    // overlapping destinations cover less than the sources, so they fail this as well
+    if self.image() != self.domain() {
+      bail!("map is not a bijection, its moved ranges don't land exactly on themselves");
+    }
+
+    Self::new(self.pieces.iter().map(|(range, dst)| {
+      let image = dst.clone()..shift(dst, &range.start, &range.end);
+      (image, range.start.clone())
+    }))
+  }
+}
+
+/// `value` moved by the shift taking `from` to `to`, `value` must not be below `from`
+fn shift<T>(to: &T, from: &T, value: &T) -> T
+where
+  T: Clone + Add<Output = T> + Sub<Output = T>,
+{
+  to.clone() + (value.clone() - from.clone())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn set(ranges: &[Range<u64>]) -> IntervalSet<u64> {
+    ranges.iter().cloned().collect()
+  }
+
+  #[test]
+  fn insert_coalesces() {
+    let set = set(&[5..8, 1..3, 3..4, 10..12, 7..10, 20..20]);
+    assert_eq!(set.ranges(), [1..4, 5..12]);
+    assert!(set.contains(&11));
+    assert!(!set.contains(&4));
+    assert!(!set.contains(&12));
+    assert_eq!(set.min(), Some(1));
+  }
+
+  #[test]
+  fn set_operations() {
+    let a = set(&[0..10, 20..30]);
+    let b = set(&[5..25, 28..40]);
+    let union = a.union(&b);
+    assert_eq!(union.ranges().len(), 1);
+    assert_eq!(union.ranges()[0], 0..40);
+    assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25, 28..30]);
+    assert_eq!(a.difference(&b).ranges(), [0..5, 25..28]);
+    assert_eq!(b.difference(&a).ranges(), [10..20, 30..40]);
+    assert!(a.difference(&a).is_empty());
+  }
+
+  #[test]
+  fn map_get_and_apply() {
+    let map = RangeMap::new([(98..100, 50), (50..98, 52)]).unwrap();
+    assert_eq!(map.get(79), 81);
+    assert_eq!(map.get(98), 50);
+    assert_eq!(map.get(10), 10);
+    assert_eq!(map.get(100), 100);
+
+    let image = map.apply(&set(&[40..60, 95..105]));
+    assert_eq!(image.ranges(), [40..62, 97..105]);
+
+    assert!(RangeMap::new([(0..10, 20), (5..6, 0)]).is_err());
+    assert!(RangeMap::new([(0..10_u8, 250)]).is_err());
+  }
+
+  #[test]
+  fn breakpoints_and_invert() {
+    let map = RangeMap::new([(98..100, 50), (50..98, 52), (10..12, 12), (12..14, 10)]).unwrap();
+    assert_eq!(map.breakpoints(), [10, 12, 14, 50, 98, 100]);
+
+    let inverse = map.invert().unwrap();
+    for value in 0..120 {
+      assert_eq!(inverse.get(map.get(value)), value, "value {value}");
+    }
+    assert_eq!(inverse.invert().unwrap(), map);
+
+    // 0..2 lands on 2..4, but 2..4 stays where it is
+    assert!(RangeMap::new([(0..2, 2)]).unwrap().invert().is_err());
+    assert!(RangeMap::new([(0..2, 5), (5..7, 6)])
+      .unwrap()
+      .invert()
+      .is_err());
+  }
+
+  #[test]
+  fn compose_matches_sequential_get() {
+    let a = RangeMap::new([(98..100, 50), (50..98, 52)]).unwrap();
+    let b = RangeMap::new([(15..52, 0), (52..54, 37), (0..15, 39)]).unwrap();
+    let c = RangeMap::new([(10..20, 100), (60..61, 5)]).unwrap();
+    let composed = a.compose(&b).compose(&c);
+
+    for value in 0..200 {
+      assert_eq!(
+        composed.get(value),
+        c.get(b.get(a.get(value))),
+        "value {value}"
+      );
+    }
+    assert_eq!(RangeMap::identity().compose(&a), a);
+    assert_eq!(composed.breakpoints()[0], 0);
+    assert_eq!(a.compose(&RangeMap::identity()), a);
+  }
+}
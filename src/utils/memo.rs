@@ -0,0 +1,133 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+/// cache for recursive solvers, keys may borrow from the input, e.g. `(&'a [usize], usize)`
+#[derive(Debug)]
+pub struct Memo<K, V> {
+  cache: HashMap<K, V>,
+  hits: usize,
+  misses: usize,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MemoStats {
+  pub hits: usize,
+  pub misses: usize,
+  /// number of cached entries
+  pub len: usize,
+}
+
+impl<K, V> Default for Memo<K, V> {
+  fn default() -> Self {
+    Self {
+      cache: HashMap::new(),
+      hits: 0,
+      misses: 0,
+    }
+  }
+}
+
+impl<K, V> Memo<K, V>
+where
+  K: Eq + Hash,
+  V: Clone,
+{
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// cached value of `key`, otherwise computed by `f` which gets the memo to recurse with
+  pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+    if let Some(value) = self.cache.get(&key) {
+      self.hits += 1;
+      return value.clone();
+    }
+
+    self.misses += 1;
+    let value = f(self);
+    self.cache.insert(key, value.clone());
+    value
+  }
+
+  pub fn stats(&self) -> MemoStats {
+    MemoStats {
+      hits: self.hits,
+      misses: self.misses,
+      len: self.cache.len(),
+    }
+  }
+
+  /// drop the cached values but keep the stats
+  pub fn clear(&mut self) {
+    self.cache.clear();
+  }
+}
+
+impl Display for MemoStats {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{} hits, {} misses, {} entries",
+      self.hits, self.misses, self.len
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+    if n < 2 {
+      return n;
+    }
+    memo.get_or_insert_with(n, |memo| fib(n - 1, memo) + fib(n - 2, memo))
+  }
+
+  #[test]
+  fn recursion_and_stats() {
+    let mut memo = Memo::new();
+    assert_eq!(fib(90, &mut memo), 2880067194370816120);
+    assert_eq!(
+      memo.stats(),
+      MemoStats {
+        hits: 87,
+        misses: 89,
+        len: 89
+      }
+    );
+
+    assert_eq!(fib(90, &mut memo), 2880067194370816120);
+    assert_eq!(memo.stats().hits, 88);
+    assert_eq!(memo.stats().to_string(), "88 hits, 89 misses, 89 entries");
+  }
+
+  #[test]
+  fn borrowed_keys() {
+    fn count_sums<'a>(
+      items: &'a [u32],
+      target: u32,
+      memo: &mut Memo<(&'a [u32], u32), u64>,
+    ) -> u64 {
+      match items.split_first() {
+        _ if target == 0 => 1,
+        None => 0,
+        Some((&first, rest)) => memo.get_or_insert_with((items, target), |memo| {
+          let with = target
+            .checked_sub(first)
+            .map_or(0, |target| count_sums(rest, target, memo));
+          with + count_sums(rest, target, memo)
+        }),
+      }
+    }
+
+    let items = vec![1; 20];
+    assert_eq!(count_sums(&items, 10, &mut Memo::new()), 184756);
+  }
+}
@@ -0,0 +1,191 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use itertools::Itertools;
+use num::{Integer, Signed};
+
+/// `(g, x, y)` with `g = gcd(a, b) = a * x + b * y` and `g >= 0`
+pub fn ext_gcd<T: Integer + Signed + Copy>(a: T, b: T) -> (T, T, T) {
+  if b.is_zero() {
+    return if a.is_negative() {
+      (-a, -T::one(), T::zero())
+    } else {
+      (a, T::one(), T::zero())
+    };
+  }
+  let (q, r) = a.div_mod_floor(&b);
+  let (g, x, y) = ext_gcd(b, r);
+  (g, y, x - q * y)
+}
+
+/// inverse of `a` modulo `m`, if they are coprime
+pub fn mod_inverse<T: Integer + Signed + Copy>(a: T, m: T) -> Option<T> {
+  let (g, x, _) = ext_gcd(a, m);
+  g.is_one().then(|| x.mod_floor(&m))
+}
+
+/// solve `x = residue (mod modulus)` for every pair, the moduli don't need to be coprime
+///
+/// returns the smallest non-negative `x` and the lcm of the moduli, every solution is `x` plus
+/// a multiple of it, `None` if the congruences contradict each other
+pub fn crt<T: Integer + Signed + Copy>(congruences: &[(T, T)]) -> Option<(T, T)> {
+  congruences
+    .iter()
+    .try_fold((T::zero(), T::one()), |(x, m), &(residue, modulus)| {
+      // x + m * k = residue (mod modulus), solve for k
+      let (g, inv, _) = ext_gcd(m, modulus);
+      let diff = residue - x;
+      if !diff.is_multiple_of(&g) {
+        return None;
+      }
+      let step = modulus / g;
+      let k = (diff / g * inv).mod_floor(&step);
+      let lcm = m * step;
+      Some(((x + m * k).mod_floor(&lcm), lcm))
+    })
+}
+
+/// times at which something happens, each time in `once`, and each of `offsets` plus any
+/// multiple of `period`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Schedule {
+  pub once: Vec<u64>,
+  pub offsets: Vec<u64>,
+  pub period: u64,
+}
+
+impl Schedule {
+  pub fn hits(&self, time: u64) -> bool {
+    self.once.contains(&time)
+      || self
+        .offsets
+        .iter()
+        .any(|&it| time >= it && (time - it).is_multiple_of(self.period))
+  }
+}
+
+/// the first time all schedules hit together
+///
+/// every combination of offsets is tried, so keep the offsets per schedule few
+pub fn first_common_time(schedules: &[Schedule]) -> Option<u64> {
+  // a common time is either one of the single hits, or a hit of every periodic part
+  let single = schedules
+    .iter()
+    .flat_map(|it| &it.once)
+    .copied()
+    .filter(|&time| schedules.iter().all(|it| it.hits(time)))
+    .min();
+
+  let periodic = schedules
+    .iter()
+    .map(|it| it.offsets.iter().map(move |&offset| (offset, it.period)))
+    .multi_cartesian_product()
+    .filter_map(|combination| {
+      let congruences = combination
+        .iter()
+        .map(|&(offset, period)| ((offset % period) as i128, period as i128))
+        .collect_vec();
+      let (x, lcm) = crt(&congruences)?;
+
+      // every offset must have passed, not just the residues agree
+      let min = combination.iter().map(|it| it.0).max()? as i128;
+      let time = if x >= min {
+        x
+      } else {
+        x + Integer::div_ceil(&(min - x), &lcm) * lcm
+      };
+      u64::try_from(time).ok()
+    })
+    .min();
+
+  single.into_iter().chain(periodic).min()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ext_gcd_identity() {
+    for a in -30_i64..30 {
+      for b in -30_i64..30 {
+        let (g, x, y) = ext_gcd(a, b);
+        assert_eq!(g, a.gcd(&b), "gcd({a}, {b})");
+        assert_eq!(a * x + b * y, g, "bezout of ({a}, {b})");
+      }
+    }
+    assert_eq!(mod_inverse(3, 11), Some(4));
+    assert_eq!(mod_inverse(4, 10), None);
+  }
+
+  #[test]
+  fn crt_coprime_and_not() {
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    assert_eq!(crt(&[(2_i64, 4), (4, 6)]), Some((10, 12)));
+    assert_eq!(crt(&[(1_i64, 4), (2, 6)]), None);
+    assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+
+    for x in 0..60_i64 {
+      let congruences = [(x % 4, 4), (x % 6, 6), (x % 10, 10)];
+      assert_eq!(crt(&congruences), Some((x, 60)));
+    }
+  }
+
+  #[test]
+  fn common_time_needs_every_offset_passed() {
+    // 2, 4, 6, .. and 3, 6, 9, ..
+    let a = Schedule {
+      once: vec![],
+      offsets: vec![2],
+      period: 2,
+    };
+    let b = Schedule {
+      once: vec![],
+      offsets: vec![3],
+      period: 3,
+    };
+    assert_eq!(first_common_time(&[a.clone(), b.clone()]), Some(6));
+
+    // offsets not aligned with the period, where lcm alone is wrong
+    let c = Schedule {
+      once: vec![],
+      offsets: vec![5],
+      period: 4,
+    };
+    assert_eq!(first_common_time(&[a.clone(), c.clone()]), None);
+    let d = Schedule {
+      once: vec![],
+      offsets: vec![7],
+      period: 6,
+    };
+    assert_eq!(first_common_time(&[b.clone(), d.clone()]), None);
+    let e = Schedule {
+      once: vec![1],
+      offsets: vec![4, 15],
+      period: 9,
+    };
+    assert_eq!(first_common_time(&[b.clone(), e.clone()]), Some(15));
+
+    // brute force agrees
+    let schedules = [a, b, e];
+    let expected = (0..1000).find(|&t| schedules.iter().all(|it| it.hits(t)));
+    assert_eq!(first_common_time(&schedules), expected);
+  }
+
+  #[test]
+  fn common_time_in_the_tail() {
+    let a = Schedule {
+      once: vec![1, 3],
+      offsets: vec![10],
+      period: 5,
+    };
+    let b = Schedule {
+      once: vec![],
+      offsets: vec![1],
+      period: 2,
+    };
+    assert_eq!(first_common_time(&[a, b]), Some(1));
+  }
+}
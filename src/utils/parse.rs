@@ -0,0 +1,109 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+/// a line of puzzle input, which knows where it is, to report readable errors
+#[derive(Debug, Copy, Clone)]
+pub struct Line<'a> {
+  idx: usize,
+  text: &'a str,
+}
+
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> + Clone {
+  input
+    .lines()
+    .enumerate()
+    .map(|(idx, text)| Line { idx, text })
+}
+
+impl<'a> Line<'a> {
+  pub fn text(&self) -> &'a str {
+    self.text
+  }
+
+  /// 1-based line number
+  pub fn number(&self) -> usize {
+    self.idx + 1
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.text.is_empty()
+  }
+
+  pub fn error(&self, msg: impl Display) -> anyhow::Error {
+    anyhow!("line {}: {msg}", self.number())
+  }
+
+  /// error at 0-based char column `col`
+  pub fn error_at_col(&self, col: usize, msg: impl Display) -> anyhow::Error {
+    anyhow!("line {}, col {}: {msg}", self.number(), col + 1)
+  }
+
+  /// error at the start of `part`, which must be a sub slice of this line
+  pub fn error_at(&self, part: &str, msg: impl Display) -> anyhow::Error {
+    self.error_at_col(self.col_of(part), msg)
+  }
+
+  /// 0-based char column of `part`, which must be a sub slice of this line
+  fn col_of(&self, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize)
+      .checked_sub(self.text.as_ptr() as usize)
+      .filter(|it| *it <= self.text.len())
+      .expect("part is not a sub slice of line");
+    self.text[..offset].chars().count()
+  }
+
+  pub fn strip_prefix(&self, prefix: &str) -> anyhow::Result<&'a str> {
+    self
+      .text
+      .strip_prefix(prefix)
+      .ok_or_else(|| self.error_at_col(0, format!("expected {prefix:?}")))
+  }
+
+  /// split `part` of this line at the first `delimiter`
+  pub fn split_once(&self, part: &'a str, delimiter: &str) -> anyhow::Result<(&'a str, &'a str)> {
+    part
+      .split_once(delimiter)
+      .ok_or_else(|| self.error_at(part, format!("expected {delimiter:?}")))
+  }
+
+  /// parse `token`, which must be a sub slice of this line
+  pub fn parse<T>(&self, token: &'a str) -> anyhow::Result<T>
+  where
+    T: FromStr,
+    T::Err: Display,
+  {
+    token
+      .parse::<T>()
+      .map_err(|err| self.error_at(token, format!("invalid number {token:?}: {err}")))
+  }
+
+  /// parse whitespace separated numbers in `part` of this line
+  pub fn parse_numbers<T>(&self, part: &'a str) -> anyhow::Result<Vec<T>>
+  where
+    T: FromStr,
+    T::Err: Display,
+  {
+    part.split_whitespace().map(|it| self.parse(it)).collect()
+  }
+
+  /// parse each char in `part` of this line with `f`, errors are reported at the char's column
+  pub fn parse_chars<T>(
+    &self,
+    part: &'a str,
+    f: impl Fn(char) -> anyhow::Result<T>,
+  ) -> anyhow::Result<Vec<T>> {
+    let col = self.col_of(part);
+    part
+      .chars()
+      .enumerate()
+      .map(|(idx, ch)| f(ch).map_err(|err| self.error_at_col(col + idx, err)))
+      .collect()
+  }
+}
@@ -0,0 +1,257 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use anyhow::bail;
+
+/// 2d point or offset, `y` grows downwards like the rows of a puzzle map
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Point<T> {
+  pub x: T,
+  pub y: T,
+}
+
+/// signed point, mostly used as offset
+pub type IPoint = Point<isize>;
+/// unsigned point, mostly used as index into a map
+pub type UPoint = Point<usize>;
+
+impl<T> Point<T> {
+  pub const fn new(x: T, y: T) -> Self {
+    Self { x, y }
+  }
+
+  /// checked conversion of both coordinates, e.g. between [`IPoint`] and [`UPoint`]
+  pub fn try_cast<U>(self) -> Option<Point<U>>
+  where
+    U: TryFrom<T>,
+  {
+    Some(Point::new(
+      U::try_from(self.x).ok()?,
+      U::try_from(self.y).ok()?,
+    ))
+  }
+}
+
+impl<T> Point<T>
+where
+  T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+  pub fn dot(self, other: Self) -> T {
+    self.x * other.x + self.y * other.y
+  }
+
+  /// z of the 3d cross product, positive when `other` is clockwise of `self` since `y` points down
+  pub fn cross(self, other: Self) -> T {
+    self.x * other.y - self.y * other.x
+  }
+}
+
+impl<T> Point<T>
+where
+  T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>,
+{
+  pub fn manhattan(self, other: Self) -> T {
+    let abs_diff = |a: T, b: T| if a > b { a - b } else { b - a };
+    abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+  }
+}
+
+impl UPoint {
+  /// move by `offset`, `None` if a coordinate would become negative
+  pub fn checked_add_signed(self, offset: IPoint) -> Option<Self> {
+    Some(Self::new(
+      self.x.checked_add_signed(offset.x)?,
+      self.y.checked_add_signed(offset.y)?,
+    ))
+  }
+
+  /// one step towards `dir`, `None` if a coordinate would become negative
+  pub fn step(self, dir: Direction) -> Option<Self> {
+    self.checked_add_signed(dir.offset())
+  }
+
+  /// signed offset from `self` to `other`
+  pub fn offset_to(self, other: Self) -> IPoint {
+    IPoint::new(
+      other.x as isize - self.x as isize,
+      other.y as isize - self.y as isize,
+    )
+  }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+  fn from((x, y): (T, T)) -> Self {
+    Self::new(x, y)
+  }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+  fn from(value: Point<T>) -> Self {
+    (value.x, value.y)
+  }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    Self::new(self.x + rhs.x, self.y + rhs.y)
+  }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self {
+    Self::new(self.x - rhs.x, self.y - rhs.y)
+  }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+  fn add_assign(&mut self, rhs: Self) {
+    self.x += rhs.x;
+    self.y += rhs.y;
+  }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+  fn sub_assign(&mut self, rhs: Self) {
+    self.x -= rhs.x;
+    self.y -= rhs.y;
+  }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    Self::new(-self.x, -self.y)
+  }
+}
+
+/// scale by a factor
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+  type Output = Self;
+
+  fn mul(self, rhs: T) -> Self {
+    Self::new(self.x * rhs, self.y * rhs)
+  }
+}
+
+impl<T: Display> Display for Point<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "({}, {})", self.x, self.y)
+  }
+}
+
+/// one of the 4 orthogonal directions on a map
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Direction {
+  Up,
+  Right,
+  Down,
+  Left,
+}
+
+impl Direction {
+  /// clockwise from up
+  pub const ALL: [Direction; 4] = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+  ];
+
+  pub fn offset(self) -> IPoint {
+    match self {
+      Direction::Up => IPoint::new(0, -1),
+      Direction::Right => IPoint::new(1, 0),
+      Direction::Down => IPoint::new(0, 1),
+      Direction::Left => IPoint::new(-1, 0),
+    }
+  }
+
+  pub fn turn_right(self) -> Self {
+    Self::ALL[(self as usize + 1) % 4]
+  }
+
+  pub fn turn_left(self) -> Self {
+    Self::ALL[(self as usize + 3) % 4]
+  }
+
+  pub fn opposite(self) -> Self {
+    Self::ALL[(self as usize + 2) % 4]
+  }
+
+  pub fn is_vertical(self) -> bool {
+    matches!(self, Direction::Up | Direction::Down)
+  }
+}
+
+impl TryFrom<IPoint> for Direction {
+  type Error = anyhow::Error;
+
+  fn try_from(value: IPoint) -> anyhow::Result<Self> {
+    match Self::ALL.into_iter().find(|it| it.offset() == value) {
+      Some(it) => Ok(it),
+      None => bail!("offset {value} is not a unit step"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn turns() {
+    for dir in Direction::ALL {
+      assert_eq!(dir.turn_left().turn_right(), dir);
+      assert_eq!(dir.turn_right().turn_right(), dir.opposite());
+      assert_eq!(dir.opposite().offset(), -dir.offset());
+      // a right turn is clockwise, which is a positive cross product
+      assert_eq!(dir.offset().cross(dir.turn_right().offset()), 1);
+      assert_eq!(dir.offset().cross(dir.turn_left().offset()), -1);
+      assert_eq!(Direction::try_from(dir.offset()).unwrap(), dir);
+    }
+    assert_eq!(Direction::Up.turn_right(), Direction::Right);
+    assert_eq!(Direction::Up.turn_left(), Direction::Left);
+    assert!(Direction::try_from(IPoint::new(1, 1)).is_err());
+  }
+
+  #[test]
+  fn arithmetic() {
+    let a = IPoint::new(3, -2);
+    let b = IPoint::new(-1, 4);
+    assert_eq!(a + b, IPoint::new(2, 2));
+    assert_eq!(a - b, IPoint::new(4, -6));
+    assert_eq!(a * 3, IPoint::new(9, -6));
+    assert_eq!(a.dot(b), -11);
+    assert_eq!(a.cross(b), 10);
+    assert_eq!(a.manhattan(b), 10);
+    assert_eq!(UPoint::new(1, 5).manhattan(UPoint::new(4, 2)), 6);
+  }
+
+  #[test]
+  fn conversion() {
+    assert_eq!(
+      IPoint::new(2, 3).try_cast::<usize>(),
+      Some(UPoint::new(2, 3))
+    );
+    assert_eq!(IPoint::new(2, -3).try_cast::<usize>(), None);
+    assert_eq!(UPoint::new(0, 0).step(Direction::Up), None);
+    assert_eq!(
+      UPoint::new(0, 0).step(Direction::Down),
+      Some(UPoint::new(0, 1))
+    );
+    assert_eq!(
+      UPoint::new(4, 1).offset_to(UPoint::new(2, 3)),
+      IPoint::new(-2, 2)
+    );
+  }
+}
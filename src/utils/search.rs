@@ -0,0 +1,320 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use num::Zero;
+
+/// distances and predecessors of every node reached from the starts
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+  dist: HashMap<N, C>,
+  prev: HashMap<N, N>,
+}
+
+impl<N, C> Search<N, C>
+where
+  N: Eq + Hash + Clone,
+  C: Copy + Ord,
+{
+  pub fn distance(&self, node: &N) -> Option<C> {
+    self.dist.get(node).copied()
+  }
+
+  pub fn distances(&self) -> &HashMap<N, C> {
+    &self.dist
+  }
+
+  pub fn contains(&self, node: &N) -> bool {
+    self.dist.contains_key(node)
+  }
+
+  /// the farthest reached node and its distance
+  pub fn farthest(&self) -> Option<(&N, C)> {
+    self
+      .dist
+      .iter()
+      .max_by_key(|(_, dist)| **dist)
+      .map(|(node, dist)| (node, *dist))
+  }
+
+  /// shortest path from one of the starts to `to`, both ends included
+  pub fn path(&self, to: &N) -> Option<Vec<N>> {
+    self.dist.get(to)?;
+
+    let mut path = vec![to.clone()];
+    while let Some(prev) = self.prev.get(path.last().unwrap()) {
+      path.push(prev.clone());
+    }
+    path.reverse();
+    Some(path)
+  }
+}
+
+/// breadth first search, every edge costs 1
+pub fn bfs<N, I>(
+  starts: impl IntoIterator<Item = N>,
+  mut neighbours: impl FnMut(&N) -> I,
+) -> Search<N, usize>
+where
+  N: Eq + Hash + Clone,
+  I: IntoIterator<Item = N>,
+{
+  let mut dist = HashMap::new();
+  let mut prev = HashMap::new();
+  let mut queue = VecDeque::new();
+  for start in starts {
+    if dist.insert(start.clone(), 0).is_none() {
+      queue.push_back(start);
+    }
+  }
+
+  while let Some(node) = queue.pop_front() {
+    let next_dist = dist[&node] + 1;
+    for next in neighbours(&node) {
+      if let Entry::Vacant(entry) = dist.entry(next.clone()) {
+        entry.insert(next_dist);
+        prev.insert(next.clone(), node.clone());
+        queue.push_back(next);
+      }
+    }
+  }
+
+  Search { dist, prev }
+}
+
+/// every node reachable from the starts, including the starts
+pub fn flood_fill<N, I>(
+  starts: impl IntoIterator<Item = N>,
+  mut neighbours: impl FnMut(&N) -> I,
+) -> HashSet<N>
+where
+  N: Eq + Hash + Clone,
+  I: IntoIterator<Item = N>,
+{
+  let mut seen = HashSet::new();
+  let mut stack = vec![];
+  for start in starts {
+    if seen.insert(start.clone()) {
+      stack.push(start);
+    }
+  }
+
+  while let Some(node) = stack.pop() {
+    for next in neighbours(&node) {
+      if seen.insert(next.clone()) {
+        stack.push(next);
+      }
+    }
+  }
+
+  seen
+}
+
+/// shortest distances with non-negative edge costs, `neighbours` yields `(node, cost)`
+pub fn dijkstra<N, C, I>(
+  starts: impl IntoIterator<Item = N>,
+  mut neighbours: impl FnMut(&N) -> I,
+) -> Search<N, C>
+where
+  N: Eq + Hash + Clone,
+  C: Copy + Ord + Add<Output = C> + Zero,
+  I: IntoIterator<Item = (N, C)>,
+{
+  let mut dist = HashMap::new();
+  let mut prev = HashMap::new();
+  // nodes don't need to be ordered, the heap holds indices into `nodes`
+  let mut nodes = vec![];
+  let mut heap = BinaryHeap::new();
+  for start in starts {
+    if dist.insert(start.clone(), C::zero()).is_none() {
+      heap.push(Reverse((C::zero(), nodes.len())));
+      nodes.push(start);
+    }
+  }
+
+  while let Some(Reverse((cost, idx))) = heap.pop() {
+    let node = nodes[idx].clone();
+    if dist[&node] < cost {
+      continue;
+    }
+
+    for (next, edge) in neighbours(&node) {
+      let next_cost = cost + edge;
+      if dist.get(&next).is_some_and(|it| *it <= next_cost) {
+        continue;
+      }
+      dist.insert(next.clone(), next_cost);
+      prev.insert(next.clone(), node.clone());
+      heap.push(Reverse((next_cost, nodes.len())));
+      nodes.push(next);
+    }
+  }
+
+  Search { dist, prev }
+}
+
+/// shortest path from `start` to the first node accepted by `is_goal`
+///
+/// `heuristic` must never overestimate the remaining cost, otherwise the path may not be the
+/// shortest, returns the cost and the path with both ends
+pub fn astar<N, C, I>(
+  start: N,
+  mut is_goal: impl FnMut(&N) -> bool,
+  mut neighbours: impl FnMut(&N) -> I,
+  mut heuristic: impl FnMut(&N) -> C,
+) -> Option<(C, Vec<N>)>
+where
+  N: Eq + Hash + Clone,
+  C: Copy + Ord + Add<Output = C> + Zero,
+  I: IntoIterator<Item = (N, C)>,
+{
+  let mut dist = HashMap::new();
+  let mut prev = HashMap::new();
+  let mut nodes = vec![];
+  let mut heap = BinaryHeap::new();
+
+  dist.insert(start.clone(), C::zero());
+  heap.push(Reverse((heuristic(&start), C::zero(), 0)));
+  nodes.push(start);
+
+  while let Some(Reverse((_, cost, idx))) = heap.pop() {
+    let node = nodes[idx].clone();
+    if dist[&node] < cost {
+      continue;
+    }
+    if is_goal(&node) {
+      let search = Search { dist, prev };
+      return Some((cost, search.path(&node).unwrap()));
+    }
+
+    for (next, edge) in neighbours(&node) {
+      let next_cost = cost + edge;
+      if dist.get(&next).is_some_and(|it| *it <= next_cost) {
+        continue;
+      }
+      dist.insert(next.clone(), next_cost);
+      prev.insert(next.clone(), node.clone());
+      heap.push(Reverse((
+        next_cost + heuristic(&next),
+        next_cost,
+        nodes.len(),
+      )));
+      nodes.push(next);
+    }
+  }
+
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::grid::Grid;
+
+  /// `#` is a wall, digits are the cost of entering a cell
+  const MAZE: &str = "\
+11111
+1###1
+19191
+1#1#1
+11111";
+
+  fn maze() -> Grid<char> {
+    Grid::parse(MAZE).unwrap()
+  }
+
+  fn open(grid: &Grid<char>, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+    grid.neighbours_4(pos).filter(|it| grid[*it] != '#')
+  }
+
+  fn weighted(
+    grid: &Grid<char>,
+    pos: (usize, usize),
+  ) -> impl Iterator<Item = ((usize, usize), u32)> + '_ {
+    open(grid, pos).map(|it| (it, grid[it].to_digit(10).unwrap()))
+  }
+
+  #[test]
+  fn bfs_distances_and_path() {
+    let grid = maze();
+    let search = bfs([(0, 0)], |pos| open(&grid, *pos).collect::<Vec<_>>());
+
+    assert_eq!(search.distance(&(4, 4)), Some(8));
+    assert_eq!(search.distance(&(2, 2)), Some(4));
+    assert_eq!(search.distance(&(1, 1)), None);
+    assert_eq!(search.farthest().map(|it| it.1), Some(8));
+
+    let path = search.path(&(2, 2)).unwrap();
+    assert_eq!(path.len(), 5);
+    assert_eq!(path[0], (0, 0));
+    assert_eq!(path[4], (2, 2));
+    assert_eq!(search.path(&(0, 0)), Some(vec![(0, 0)]));
+  }
+
+  #[test]
+  fn bfs_multiple_starts() {
+    let grid = maze();
+    let search = bfs([(0, 0), (4, 4)], |pos| {
+      open(&grid, *pos).collect::<Vec<_>>()
+    });
+    assert_eq!(search.distance(&(4, 0)), Some(4));
+    assert_eq!(search.distance(&(2, 4)), Some(2));
+  }
+
+  #[test]
+  fn dijkstra_avoids_expensive_cells() {
+    let grid = maze();
+    let search = dijkstra([(0, 0)], |pos| weighted(&grid, *pos).collect::<Vec<_>>());
+
+    // around through the bottom row instead of over the 9
+    assert_eq!(search.distance(&(2, 2)), Some(8));
+    assert_eq!(search.distance(&(1, 2)), Some(2 + 9));
+    assert_eq!(search.path(&(4, 4)).unwrap().len(), 9);
+  }
+
+  #[test]
+  fn astar_matches_dijkstra() {
+    let grid = maze();
+    let goal = (2, 3);
+    let expected = dijkstra([(0, 0)], |pos| weighted(&grid, *pos).collect::<Vec<_>>());
+
+    let (cost, path) = astar(
+      (0, 0),
+      |it| *it == goal,
+      |pos| weighted(&grid, *pos).collect::<Vec<_>>(),
+      |it| (it.0.abs_diff(goal.0) + it.1.abs_diff(goal.1)) as u32,
+    )
+    .unwrap();
+    assert_eq!(Some(cost), expected.distance(&goal));
+    assert_eq!(path.first(), Some(&(0, 0)));
+    assert_eq!(path.last(), Some(&goal));
+
+    let unreachable = astar(
+      (0, 0),
+      |it| *it == (1, 1),
+      |pos| weighted(&grid, *pos).collect::<Vec<_>>(),
+      |_| 0,
+    );
+    assert_eq!(unreachable, None);
+  }
+
+  #[test]
+  fn flood_fill_stops_at_walls() {
+    let grid = Grid::<char>::parse("..#..\n..#..").unwrap();
+    let filled = flood_fill([(0, 0)], |pos| {
+      grid
+        .neighbours_4(*pos)
+        .filter(|it| grid[*it] == '.')
+        .collect::<Vec<_>>()
+    });
+    assert_eq!(filled.len(), 4);
+    assert!(!filled.contains(&(3, 0)));
+  }
+}
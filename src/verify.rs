@@ -0,0 +1,151 @@
+/*
+ * Copyright 2023 Golden_Water
+ * All rights reserved
+ */
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+
+use crate::answer::Answer;
+use crate::input::Inputs;
+use crate::run_all::{self, Outcome, PartResult};
+
+/// expected answers of a day, a part without answer is not checked
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DayAnswers {
+  pub part_1: Option<Answer>,
+  pub part_2: Option<Answer>,
+}
+
+impl DayAnswers {
+  pub fn part(&self, part: u8) -> Option<&Answer> {
+    match part {
+      1 => self.part_1.as_ref(),
+      2 => self.part_2.as_ref(),
+      _ => None,
+    }
+  }
+}
+
+/// expected answers of a year by day, stored as `<inputs-dir>/<year>/answers.json`
+pub type Answers = BTreeMap<u8, DayAnswers>;
+
+pub fn load_answers(inputs: &Inputs, year: u16) -> anyhow::Result<Answers> {
+  let path = inputs.answers_path(year);
+  if !path.is_file() {
+    bail!("missing expected answers {}", path.display());
+  }
+
+  let content =
+    std::fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+  serde_json::from_str(&content).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+#[derive(Debug)]
+enum Status {
+  Pass(Answer),
+  Fail {
+    actual: Answer,
+    expected: Answer,
+  },
+  Error(String),
+  /// no input or no expected answer
+  Skip(String),
+}
+
+fn status(inputs: &Inputs, year: u16, answers: &Answers, result: &PartResult) -> Status {
+  let expected = answers.get(&result.day).and_then(|it| it.part(result.part));
+  match (&result.outcome, expected) {
+    (_, None) => Status::Skip("no expected answer".to_string()),
+    (Outcome::Skip, _) => Status::Skip(format!(
+      "missing input {}",
+      inputs.path(year, result.day, None).display()
+    )),
+    (Outcome::Error(err), _) => Status::Error(err.clone()),
+    (Outcome::Panic(msg), _) => Status::Error(format!("panic: {msg}")),
+    // compare printed form, so `"42"` in the answers file matches `42`
+    (Outcome::Answer(actual), Some(expected)) if actual.to_string() == expected.to_string() => {
+      Status::Pass(actual.clone())
+    }
+    (Outcome::Answer(actual), Some(expected)) => Status::Fail {
+      actual: actual.clone(),
+      expected: expected.clone(),
+    },
+  }
+}
+
+/// run every solution of `year`, print a pass/fail table, and error if anything failed
+pub fn verify(inputs: &Inputs, year: u16) -> anyhow::Result<()> {
+  let answers = load_answers(inputs, year)?;
+  let (results, _) = run_all::run_year(inputs, year, 1)?;
+  let results = results
+    .iter()
+    .map(|it| (it.day, it.part, status(inputs, year, &answers, it)))
+    .collect::<Vec<_>>();
+
+  println!("day  part  status  detail");
+  for (day, part, status) in &results {
+    let (name, detail) = match status {
+      Status::Pass(answer) => ("pass", answer.to_string()),
+      Status::Fail { actual, expected } => ("FAIL", format!("got {actual}, expected {expected}")),
+      Status::Error(err) => ("ERROR", err.clone()),
+      Status::Skip(reason) => ("skip", reason.clone()),
+    };
+    println!("{day:>3}  {part:>4}  {name:<6}  {detail}");
+  }
+
+  let count = |f: fn(&Status) -> bool| results.iter().filter(|it| f(&it.2)).count();
+  let passed = count(|it| matches!(it, Status::Pass(_)));
+  let failed = count(|it| matches!(it, Status::Fail { .. } | Status::Error(_)));
+  let skipped = count(|it| matches!(it, Status::Skip(_)));
+  println!("\n{passed} passed, {failed} failed, {skipped} skipped");
+
+  if failed > 0 {
+    bail!("{failed} of {} checked parts failed", passed + failed);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::*;
+
+  #[test]
+  fn statuses() {
+    let inputs = Inputs::new("inputs");
+    let answers: Answers =
+      serde_json::from_str(r#"{"1": {"part_1": 42}, "2": {"part_1": "7"}}"#).unwrap();
+    let status = |day, part, outcome| {
+      let result = PartResult {
+        day,
+        part,
+        outcome,
+        elapsed: Duration::ZERO,
+      };
+      format!("{:?}", status(&inputs, 1, &answers, &result))
+    };
+
+    assert_eq!(
+      status(1, 1, Outcome::Answer(Answer::from(42))),
+      "Pass(Int(42))"
+    );
+    assert_eq!(
+      status(2, 1, Outcome::Answer(Answer::from(8))),
+      "Fail { actual: Int(8), expected: Int(7) }"
+    );
+    assert_eq!(
+      status(1, 1, Outcome::Panic("boom".to_string())),
+      "Error(\"panic: boom\")"
+    );
+    assert_eq!(
+      status(1, 2, Outcome::Panic("boom".to_string())),
+      "Skip(\"no expected answer\")"
+    );
+    assert!(status(2, 1, Outcome::Skip).starts_with("Skip(\"missing input"));
+  }
+}
@@ -18,3 +18,23 @@ pub mod day_6;
 pub mod day_7;
 pub mod day_8;
 pub mod day_9;
+
+use crate::registry::Solutions;
+
+pub const SOLUTIONS: Solutions = &[
+  &day_1::Day1,
+  &day_2::Day2,
+  &day_3::Day3,
+  &day_4::Day4,
+  &day_5::Day5,
+  &day_6::Day6,
+  &day_7::Day7,
+  &day_8::Day8,
+  &day_9::Day9,
+  &day_10::Day10,
+  &day_11::Day11,
+  &day_12::Day12,
+  &day_13::Day13,
+  &day_14::Day14,
+  &day_15::Day15,
+];
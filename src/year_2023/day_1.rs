@@ -5,39 +5,62 @@
 
 use std::cmp::Ordering;
 
+use anyhow::anyhow;
 use itertools::Itertools;
 
-pub fn part_1(input: String) {
-  let sum = input
-    .split('\n')
-    .map(|it| {
-      let first = it
-        .chars()
-        .find(char::is_ascii_digit)
-        .unwrap()
-        .to_digit(10)
-        .unwrap();
-      let last = it
-        .chars()
-        .rfind(char::is_ascii_digit)
-        .unwrap()
-        .to_digit(10)
-        .unwrap();
-      first * 10 + last
+use crate::answer::Answer;
+use crate::solution::Solution;
+
+#[derive(Debug)]
+pub struct Day1;
+
+impl Solution for Day1 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 1;
+  const TITLE: &'static str = "Trebuchet?!";
+
+  type Input = Vec<String>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    Ok(input.lines().map(str::to_string).collect_vec())
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_1(input)
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
+  }
+}
+
+fn part_1(lines: &[String]) -> anyhow::Result<Answer> {
+  let sum = lines
+    .iter()
+    .enumerate()
+    .map(|(idx, it)| {
+      let to_digit = |ch: Option<char>| {
+        ch.and_then(|it| it.to_digit(10))
+          .ok_or_else(|| anyhow!("line {}: no digit in {it:?}", idx + 1))
+      };
+      let first = to_digit(it.chars().find(char::is_ascii_digit))?;
+      let last = to_digit(it.chars().rfind(char::is_ascii_digit))?;
+      Ok(first * 10 + last)
     })
-    .sum::<u32>();
+    .sum::<anyhow::Result<u32>>()?;
 
-  println!("{sum}");
+  Ok(sum.into())
 }
 
-pub fn part_2(input: String) {
+fn part_2(lines: &[String]) -> anyhow::Result<Answer> {
   const DIGITS: [&str; 9] = [
     "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
   ];
 
-  let sum = input
-    .split('\n')
-    .map(|it| {
+  let sum = lines
+    .iter()
+    .enumerate()
+    .map(|(idx, it)| {
       let first = it
         .char_indices()
         .find(|(_, it)| it.is_ascii_digit())
@@ -64,29 +87,86 @@ pub fn part_2(input: String) {
         digit: Option<(usize, u32)>,
         word: Option<(usize, usize)>,
         ordering: Ordering,
-      ) -> u32 {
+      ) -> Option<u32> {
         match (digit, word) {
           (Some((idx, v)), Some((str_idx, str_v))) => {
             if idx.cmp(&str_idx) == ordering {
-              v
+              Some(v)
             } else {
-              str_v as u32
+              Some(str_v as u32)
             }
           }
-          (None, Some((_, v))) => v as u32,
-          (Some((_, v)), None) => v,
-          _ => {
-            unreachable!()
-          }
+          (None, Some((_, v))) => Some(v as u32),
+          (Some((_, v)), None) => Some(v),
+          (None, None) => None,
         }
       }
 
       let first = choose(first, first_str, Ordering::Less);
       let last = choose(last, last_str, Ordering::Greater);
 
-      first * 10 + last
+      match (first, last) {
+        (Some(first), Some(last)) => Ok(first * 10 + last),
+        _ => Err(anyhow!(
+          "line {}: no digit or spelled digit in {it:?}",
+          idx + 1
+        )),
+      }
     })
-    .sum::<u32>();
+    .sum::<anyhow::Result<u32>>()?;
+
+  Ok(sum.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE_1: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+  const EXAMPLE_2: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+  #[test]
+  fn example_1_part_1() {
+    assert_eq!(solve::<Day1>(EXAMPLE_1, 1).unwrap(), Answer::from(142));
+  }
+
+  #[test]
+  fn example_2_part_2() {
+    assert_eq!(solve::<Day1>(EXAMPLE_2, 2).unwrap(), Answer::from(281));
+  }
+
+  #[test]
+  fn single_digit_is_first_and_last() {
+    assert_eq!(solve::<Day1>("a7b", 1).unwrap(), Answer::from(77));
+    assert_eq!(solve::<Day1>("seven", 2).unwrap(), Answer::from(77));
+  }
+
+  #[test]
+  fn overlapping_spelled_digits() {
+    assert_eq!(solve::<Day1>("oneight", 2).unwrap(), Answer::from(18));
+    assert_eq!(solve::<Day1>("8twone", 2).unwrap(), Answer::from(81));
+  }
+
+  #[test]
+  fn line_without_digit_is_an_error() {
+    let err = solve::<Day1>("1abc2\nabc", 1).unwrap_err();
+    assert_eq!(err.to_string(), "line 2: no digit in \"abc\"");
 
-  println!("{sum}");
+    // a spelled digit is not a digit in part 1
+    assert!(solve::<Day1>("one", 1).is_err());
+    assert!(solve::<Day1>("abc", 2).is_err());
+  }
 }
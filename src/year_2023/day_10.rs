@@ -3,18 +3,40 @@
  * All rights reserved
  */
 
-use std::ops::Neg;
-use std::sync::atomic::AtomicU8;
-use std::sync::atomic::Ordering::Relaxed;
-
+use anyhow::bail;
 use itertools::Itertools;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::grid::Grid;
+use crate::utils::point::Direction;
+use crate::utils::search;
+
+#[derive(Debug)]
+pub struct Day10;
+
+impl Solution for Day10 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 10;
+  const TITLE: &'static str = "Pipe Maze";
+
+  type Input = Grid<Pipe>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    Grid::parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_1(input)
+  }
 
-const DIRS: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
+  }
+}
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
-enum Pipe {
+pub enum Pipe {
   Vertical,
   Horizontal,
   TopRight,
@@ -26,71 +48,52 @@ enum Pipe {
 }
 
 impl Pipe {
-  pub fn is_connected(&self, to: Pipe, off: (isize, isize)) -> bool {
-    match off {
-      (0, -1) => {
+  pub fn is_connected(&self, to: Pipe, dir: Direction) -> bool {
+    match dir {
+      Direction::Up => {
         matches!(
           *self,
           Pipe::Start | Pipe::Vertical | Pipe::TopLeft | Pipe::TopRight
         ) && matches!(to, Pipe::Vertical | Pipe::BottomRight | Pipe::BottomLeft)
       }
-      (0, 1) => {
+      Direction::Down => {
         matches!(
           *self,
           Pipe::Start | Pipe::Vertical | Pipe::BottomRight | Pipe::BottomLeft
         ) && matches!(to, Pipe::Vertical | Pipe::TopLeft | Pipe::TopRight)
       }
-      (-1, 0) => {
+      Direction::Left => {
         matches!(
           *self,
           Pipe::Start | Pipe::Horizontal | Pipe::BottomLeft | Pipe::TopLeft
         ) && matches!(to, Pipe::Horizontal | Pipe::BottomRight | Pipe::TopRight)
       }
-      (1, 0) => {
+      Direction::Right => {
         matches!(
           *self,
           Pipe::Start | Pipe::Horizontal | Pipe::BottomRight | Pipe::TopRight
         ) && matches!(to, Pipe::Horizontal | Pipe::BottomLeft | Pipe::TopLeft)
       }
-      (_, _) => {
-        unreachable!()
-      }
     }
   }
 }
 
-fn get_connected_pipes(
-  map: &[Vec<(Pipe, AtomicU8)>],
-  x: usize,
-  y: usize,
-  visited: u8,
-  unvisited: u8,
-) -> Vec<(usize, usize)> {
-  let loc = &map[y][x];
-  loc.1.store(visited, Relaxed);
-  let pipe = loc.0;
-
-  DIRS
-    .into_iter()
-    .filter_map(|(x_off, y_off)| {
-      if let (Some(x), Some(y)) = (x.checked_add_signed(x_off), y.checked_add_signed(y_off)) {
-        if y < map.len() && x < map[y].len() {
-          let it = &map[y][x];
-          (it.1.load(Relaxed) == unvisited && pipe.is_connected(it.0, (x_off, y_off)))
-            .then_some((x, y))
-        } else {
-          None
-        }
-      } else {
-        None
-      }
-    })
-    .collect_vec()
+/// directions and positions of the pipes connected to the pipe at `pos`
+fn connected(
+  map: &Grid<Pipe>,
+  pos: (usize, usize),
+) -> impl Iterator<Item = (Direction, (usize, usize))> + '_ {
+  Direction::ALL.into_iter().filter_map(move |dir| {
+    let next = map.step(pos, dir)?;
+    map[pos].is_connected(map[next], dir).then_some((dir, next))
+  })
 }
 
-impl From<char> for Pipe {
-  fn from(value: char) -> Self {
-    match value {
+impl TryFrom<char> for Pipe {
+  type Error = anyhow::Error;
+
+  fn try_from(value: char) -> anyhow::Result<Self> {
+    Ok(match value {
       '|' => Self::Vertical,
       '-' => Self::Horizontal,
       'L' => Self::TopRight,
@@ -100,177 +103,197 @@ impl From<char> for Pipe {
       'S' => Self::Start,
       '.' => Self::Empty,
       _ => {
-        unreachable!()
+        bail!("unexpected char {value:?} in pipe map")
       }
-    }
+    })
   }
 }
 
-pub fn part_1(input: String) {
-  let map = parse(input);
-  let mut search_front = get_starts(&map).2;
-
-  let mut count = 0;
-  while !search_front.is_empty() {
-    search_front = search_front
-      .into_iter()
-      .flat_map(|(x, y)| get_connected_pipes(&map, x, y, 1, 0))
-      .collect_vec();
-    count += 1;
-  }
+fn part_1(map: &Grid<Pipe>) -> anyhow::Result<Answer> {
+  let (start, _) = get_starts(map)?;
+  let search = search::bfs([start], |pos| connected(map, *pos).map(|it| it.1));
 
-  count.println();
+  Ok(search.farthest().map_or(0, |it| it.1).into())
 }
 
-pub fn part_2(input: String) {
-  let map = parse(input);
-  let (x, y, starts) = get_starts(&map);
-  let mut search_front = starts.clone();
+/// cell state of part 2
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Flag {
+  Unknown,
+  /// connected to the start, not walked yet
+  Pipe,
+  Walked,
+  Left,
+  Right,
+}
+
+fn part_2(map: &Grid<Pipe>) -> anyhow::Result<Answer> {
+  let (start, starts) = get_starts(map)?;
+  let search = search::bfs([start], |pos| connected(map, *pos).map(|it| it.1));
 
-  while !search_front.is_empty() {
-    search_front = search_front
-      .into_iter()
-      .flat_map(|(x, y)| get_connected_pipes(&map, x, y, 1, 0))
-      .collect_vec();
+  let mut flags = map.map(|_| Flag::Unknown);
+  for pos in search.distances().keys() {
+    flags[*pos] = Flag::Pipe;
   }
 
-  map[y][x].1.store(2, Relaxed);
-  let mut prev = (x, y);
-  let mut cur = Some(starts[0]);
+  flags[start] = Flag::Walked;
+  let (mut dir, mut cur) = starts[0];
 
   loop {
-    let (x, y) = cur.take().unwrap();
-    let next = get_connected_pipes(&map, x, y, 2, 1);
-    let next = next.into_iter().next();
-
-    let next = if let Some(next) = next { next } else { break };
-
-    let prev_off = (
-      (prev.0 as isize).wrapping_sub_unsigned(x),
-      (prev.1 as isize).wrapping_sub_unsigned(y),
-    );
-    let cur_dir = (
-      (x as isize).wrapping_sub_unsigned(prev.0),
-      (y as isize).wrapping_sub_unsigned(prev.1),
-    );
-    let next_off = (
-      (next.0 as isize).wrapping_sub_unsigned(x),
-      (next.1 as isize).wrapping_sub_unsigned(y),
-    );
-
-    /// return true if turn right
-    fn is_turn_right(to_prev: (isize, isize), to_next: (isize, isize)) -> bool {
-      let (x1, y1) = (to_prev.0 as f64, to_prev.1 as f64);
-      let (x2, y2) = (to_next.0 as f64, to_next.1 as f64);
-      let dot = x1 * x2 + y1 * y2;
-      let det = x1 * y2 - y1 * x2;
-      det.atan2(dot).is_sign_negative()
-    }
+    flags[cur] = Flag::Walked;
+    let Some((next_dir, next)) = connected(map, cur).find(|it| flags[it.1] == Flag::Pipe) else {
+      break;
+    };
 
-    // left: false, right: true
-    let straight = cur_dir == next_off;
-    let dirs = if straight {
-      DIRS
-        .into_iter()
-        .filter(|it| (next_off.0 * it.0 + next_off.1 * it.1) == 0)
-        .map(|it| (it, is_turn_right(prev_off, it)))
-        .collect_vec()
+    // cells beside `cur` with their side of the walk, left: false, right: true
+    let sides = if next_dir == dir {
+      [(dir.turn_left(), false), (dir.turn_right(), true)]
     } else {
-      let is_right = !is_turn_right(prev_off, next_off);
-      vec![
-        (cur_dir, is_right),
-        ((next_off.0.neg(), next_off.1.neg()), is_right),
-      ]
+      // both cells are on the outer side of the turn
+      let is_right = next_dir == dir.turn_left();
+      [(dir, is_right), (next_dir.opposite(), is_right)]
     };
 
-    for ((x_off, y_off), is_right) in dirs.into_iter() {
-      let next = (x.checked_add_signed(x_off), y.checked_add_signed(y_off));
-      if let (Some(x), Some(y)) = next {
-        if y < map.len() && x < map[y].len() && map[y][x].1.load(Relaxed) == 0 {
-          map[y][x].1.store(is_right as u8 + 3, Relaxed);
+    for (side_dir, is_right) in sides {
+      if let Some(side) = map.step(cur, side_dir) {
+        if flags[side] == Flag::Unknown {
+          flags[side] = if is_right { Flag::Right } else { Flag::Left };
         }
       }
     }
 
-    prev = (x, y);
-    cur = Some(next);
+    dir = next_dir;
+    cur = next;
   }
 
-  let mut outer_or_inner = map
-    .iter()
-    .enumerate()
-    .flat_map(|(y, line)| {
-      line
-        .iter()
-        .enumerate()
-        .filter_map(|(x, (_, flag))| {
-          (flag.load(Relaxed) == 3 || flag.load(Relaxed) == 4).then_some((x, y))
-        })
-        .collect_vec()
-    })
-    .collect_vec();
-
-  while !outer_or_inner.is_empty() {
-    outer_or_inner = outer_or_inner
-      .into_iter()
-      .flat_map(|(x, y)| {
-        let cur = map[y][x].1.load(Relaxed);
-
-        DIRS
-          .into_iter()
-          .filter_map(|(x_off, y_off)| {
-            let next = (x.checked_add_signed(x_off), y.checked_add_signed(y_off));
-            if let (Some(x), Some(y)) = next {
-              if y < map.len() && x < map[y].len() && map[y][x].1.load(Relaxed) == 0 {
-                map[y][x].1.store(cur, Relaxed);
-                Some((x, y))
-              } else {
-                None
-              }
-            } else {
-              None
-            }
-          })
+  // spread each side over the cells not on the pipes
+  for side in [Flag::Left, Flag::Right] {
+    let filled = search::flood_fill(
+      flags.iter().filter(|it| *it.1 == side).map(|it| it.0),
+      |pos| {
+        flags
+          .neighbours_4(*pos)
+          .filter(|it| flags[*it] == Flag::Unknown)
           .collect_vec()
-      })
-      .collect_vec();
+      },
+    );
+    filled.into_iter().for_each(|it| flags[it] = side);
   }
 
-  for line in map.iter() {
-    line.iter().map(|it| it.1.load(Relaxed)).join("").println();
-  }
+  let count_side = |side: Flag| flags.iter().filter(|it| *it.1 == side).count();
 
-  map
+  // the side which reaches the border is the outside of the loop
+  let (width, height) = (flags.width(), flags.height());
+  let outer = flags
     .iter()
-    .map(|it| it.iter().filter(|it| it.1.load(Relaxed) == 3).count())
-    .sum::<usize>()
-    .println();
-  map
-    .iter()
-    .map(|it| it.iter().filter(|it| it.1.load(Relaxed) == 4).count())
-    .sum::<usize>()
-    .println();
+    .filter(|((x, y), _)| *y == 0 || *y == height - 1 || *x == 0 || *x == width - 1)
+    .map(|(_, flag)| *flag)
+    .find(|it| matches!(it, Flag::Left | Flag::Right));
+
+  let inner = match outer {
+    Some(Flag::Left) => count_side(Flag::Right),
+    Some(_) => count_side(Flag::Left),
+    // loop covers the whole border, so there is no outside
+    None => count_side(Flag::Left).max(count_side(Flag::Right)),
+  };
+  Ok(inner.into())
 }
 
-fn get_starts(map: &[Vec<(Pipe, AtomicU8)>]) -> (usize, usize, Vec<(usize, usize)>) {
-  for (y, line) in map.iter().enumerate() {
-    for (x, &(pipe, _)) in line.iter().enumerate() {
-      if pipe == Pipe::Start {
-        return (x, y, get_connected_pipes(map, x, y, 1, 0));
-      }
-    }
+/// the start tile and the pipes connected to it
+type Starts = ((usize, usize), Vec<(Direction, (usize, usize))>);
+
+fn get_starts(map: &Grid<Pipe>) -> anyhow::Result<Starts> {
+  let Some((start, _)) = map.iter().find(|it| *it.1 == Pipe::Start) else {
+    bail!("no start tile 'S' in pipe map")
+  };
+
+  let starts = connected(map, start).collect_vec();
+  if starts.is_empty() {
+    bail!(
+      "line {}, col {}: start tile is not connected to any pipe",
+      start.1 + 1,
+      start.0 + 1
+    );
   }
-  unreachable!()
+  Ok((start, starts))
 }
 
-fn parse(input: String) -> Vec<Vec<(Pipe, AtomicU8)>> {
-  input
-    .lines()
-    .map(|it| it.chars().map(Pipe::from).collect_vec())
-    .map(|it| {
-      it.into_iter()
-        .map(|it| (it, AtomicU8::new(0)))
-        .collect_vec()
-    })
-    .collect_vec()
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE_1: &str = "\
+.....
+.S-7.
+.|.|.
+.L-J.
+.....";
+
+  const EXAMPLE_2: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...";
+
+  const EXAMPLE_3: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+  const EXAMPLE_4: &str = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+
+  const EXAMPLE_5: &str = "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
+
+  #[test]
+  fn example_1_part_1() {
+    assert_eq!(solve::<Day10>(EXAMPLE_1, 1).unwrap(), Answer::from(4));
+  }
+
+  #[test]
+  fn example_2_part_1() {
+    assert_eq!(solve::<Day10>(EXAMPLE_2, 1).unwrap(), Answer::from(8));
+  }
+
+  #[test]
+  fn example_3_part_2() {
+    assert_eq!(solve::<Day10>(EXAMPLE_3, 2).unwrap(), Answer::from(4));
+  }
+
+  #[test]
+  fn example_4_part_2() {
+    assert_eq!(solve::<Day10>(EXAMPLE_4, 2).unwrap(), Answer::from(8));
+  }
+
+  #[test]
+  fn example_5_part_2() {
+    assert_eq!(solve::<Day10>(EXAMPLE_5, 2).unwrap(), Answer::from(10));
+  }
 }
@@ -3,40 +3,39 @@
  * All rights reserved
  */
 
+use anyhow::bail;
 use itertools::Itertools;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::checked::{self, Int};
+use crate::utils::parse;
 
-pub fn part_1(input: String) {
-  let mut map = parse(input);
+#[derive(Debug)]
+pub struct Day11;
 
-  let mut i = 0;
-  while i < map.len() {
-    let empty = !map[i].iter().any(|it| *it);
-    if empty {
-      map.insert(i, map[i].clone());
-      i += 1;
-    }
-    i += 1;
-  }
+impl Solution for Day11 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 11;
+  const TITLE: &'static str = "Cosmic Expansion";
+
+  type Input = Vec<Vec<bool>>;
 
-  let mut i = 0;
-  while i < map[0].len() {
-    let empty = !map.iter().map(|it| it[i]).any(|it| it);
-    if empty {
-      map.iter_mut().for_each(|it| it.insert(i, false));
-      i += 1;
-    }
-    i += 1;
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
   }
 
-  let galaxies = extract_galaxies(&map);
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_1(input)
+  }
 
-  calc_distance(galaxies).println();
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
+  }
+}
 
-  // for line in map {
-  //   line.iter().map(|&it| it as usize).join("").println();
-  // }
+fn part_1(map: &[Vec<bool>]) -> anyhow::Result<Answer> {
+  Ok(expanded_distance::<u64>(map, 2)?.into())
 }
 
 fn extract_galaxies(map: &[Vec<bool>]) -> Vec<(usize, usize)> {
@@ -53,63 +52,113 @@ fn extract_galaxies(map: &[Vec<bool>]) -> Vec<(usize, usize)> {
     .collect_vec()
 }
 
-fn parse(input: String) -> Vec<Vec<bool>> {
-  input
-    .lines()
-    .map(|it| it.chars().map(|it| matches!(it, '#')).collect_vec())
-    .collect_vec()
+fn parse(input: &str) -> anyhow::Result<Vec<Vec<bool>>> {
+  parse::lines(input)
+    .map(|line| {
+      line.parse_chars(line.text(), |it| match it {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => bail!("unexpected char {it:?} in image"),
+      })
+    })
+    .collect()
 }
 
-fn calc_distance(galaxies: Vec<(usize, usize)>) -> usize {
-  galaxies
-    .iter()
-    .combinations(2)
-    .map(|it| it[0].0.abs_diff(it[1].0) + it[0].1.abs_diff(it[1].1))
-    .sum::<usize>()
+fn calc_distance<T: Int>(galaxies: &[(T, T)]) -> anyhow::Result<T> {
+  checked::sum(
+    galaxies
+      .iter()
+      .tuple_combinations()
+      .map(|(a, b)| {
+        checked::add(
+          &checked::abs_diff(&a.0, &b.0),
+          &checked::abs_diff(&a.1, &b.1),
+        )
+      })
+      .collect::<anyhow::Result<Vec<_>>>()?,
+  )
 }
 
-pub fn part_2(input: String) {
-  const EXPAND_BY: usize = 1_000_000 - 1;
+fn part_2(map: &[Vec<bool>]) -> anyhow::Result<Answer> {
+  Ok(expanded_distance::<u64>(map, 1_000_000)?.into())
+}
 
-  let map = parse(input);
-  let mut galaxies = extract_galaxies(&map);
+/// sum of distances with every empty row and column replaced by `factor` of them
+fn expanded_distance<T: Int>(map: &[Vec<bool>], factor: T) -> anyhow::Result<T> {
+  let expand_by = checked::sub(&factor, &T::one())?;
+
+  let galaxies = extract_galaxies(map);
+
+  // each coordinate moves by the empty lines before it
+  let expand = |axis: Vec<usize>| -> anyhow::Result<Vec<T>> {
+    let occupied = axis.iter().copied().sorted().dedup().collect_vec();
+    axis
+      .iter()
+      .map(|&it| {
+        let empty = it - occupied.partition_point(|&x| x < it);
+        checked::add(
+          &checked::cast(it)?,
+          &checked::mul(&checked::cast(empty)?, &expand_by)?,
+        )
+      })
+      .collect()
+  };
+  let xs = expand(galaxies.iter().map(|it| it.0).collect_vec())?;
+  let ys = expand(galaxies.iter().map(|it| it.1).collect_vec())?;
+
+  calc_distance(&xs.into_iter().zip(ys).collect_vec())
+}
 
-  let x_axis = galaxies
-    .iter()
-    .map(|it| it.0)
-    .sorted()
-    .dedup()
-    .collect_vec();
-  let x_len = *x_axis.last().unwrap();
-
-  (0..=x_len)
-    .rev()
-    .filter(|&x| !x_axis.iter().any(|&it| it == x))
-    .for_each(|x| {
-      galaxies
-        .iter_mut()
-        .filter(|it| it.0 > x)
-        .for_each(|it| it.0 += EXPAND_BY);
-    });
-
-  let y_axis = galaxies
-    .iter()
-    .map(|it| it.1)
-    .sorted()
-    .dedup()
-    .collect_vec();
-  let y_len = *y_axis.last().unwrap();
-
-  (0..=y_len)
-    .rev()
-    .filter(|&y| !y_axis.iter().any(|&it| it == y))
-    .for_each(|y| {
-      galaxies
-        .iter_mut()
-        .filter(|it| it.1 > y)
-        .for_each(|it| it.1 += EXPAND_BY);
-    });
-
-  galaxies.println_dbg();
-  calc_distance(galaxies).println();
+#[cfg(test)]
+mod tests {
+  use num::BigInt;
+
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day11>(EXAMPLE, 1).unwrap(), Answer::from(374));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day11>(EXAMPLE, 2).unwrap(), Answer::from(82000210));
+  }
+
+  #[test]
+  fn example_smaller_expansions() {
+    let map = parse(EXAMPLE).unwrap();
+    assert_eq!(expanded_distance(&map, 2_u64).unwrap(), 374);
+    assert_eq!(expanded_distance(&map, 10_u64).unwrap(), 1030);
+    assert_eq!(expanded_distance(&map, 100_u64).unwrap(), 8410);
+  }
+
+  #[test]
+  fn expansion_overflow() {
+    let map = parse(EXAMPLE).unwrap();
+    assert!(expanded_distance(&map, 1_000_000_u32).is_ok());
+    assert!(expanded_distance(&map, 100_000_000_u32).is_err());
+
+    // a single pair, whose x and y distances only overflow added up
+    let pair = parse("#..\n...\n..#").unwrap();
+    assert!(expanded_distance(&pair, 2_000_000_000_u32).is_ok());
+    assert!(expanded_distance(&pair, 2_200_000_000_u32).is_err());
+
+    let factor = BigInt::from(10).pow(30);
+    let expected = BigInt::from(82) * &factor + 210;
+    assert_eq!(expanded_distance(&map, factor).unwrap(), expected);
+  }
 }
@@ -3,58 +3,70 @@
  * All rights reserved
  */
 
-use std::collections::HashMap;
-use std::hash::Hash;
-
+use anyhow::bail;
 use itertools::Itertools;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::memo::Memo;
+use crate::utils::parse;
+
+#[derive(Debug)]
+pub struct Day12;
+
+impl Solution for Day12 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 12;
+  const TITLE: &'static str = "Hot Springs";
+
+  type Input = Vec<(Vec<Spring>, Vec<usize>)>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_1(input))
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_2(input))
+  }
+}
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum Spring {
+pub enum Spring {
   Ok,
   Dmg,
   Unknown,
 }
 
-fn parse(input: String) -> Vec<(Vec<Spring>, Vec<usize>)> {
-  input
-    .lines()
-    .map(|it| {
-      it.split_once(' ').unwrap().then(|(springs, groups)| {
-        let springs = springs
-          .chars()
-          .map(|it| match it {
-            '.' => Spring::Ok,
-            '#' => Spring::Dmg,
-            '?' => Spring::Unknown,
-            _ => {
-              unreachable!()
-            }
-          })
-          .collect_vec();
-        let groups = groups
-          .split(',')
-          .map(str::parse::<usize>)
-          .map(Result::unwrap)
-          .collect_vec();
-        (springs, groups)
-      })
+fn parse(input: &str) -> anyhow::Result<Vec<(Vec<Spring>, Vec<usize>)>> {
+  parse::lines(input)
+    .map(|line| {
+      let (springs, groups) = line.split_once(line.text(), " ")?;
+      let springs = line.parse_chars(springs, |it| match it {
+        '.' => Ok(Spring::Ok),
+        '#' => Ok(Spring::Dmg),
+        '?' => Ok(Spring::Unknown),
+        _ => bail!("unexpected char {it:?} in spring record"),
+      })?;
+      let groups = groups
+        .split(',')
+        .map(|it| line.parse::<usize>(it))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+      Ok((springs, groups))
     })
-    .collect_vec()
+    .collect()
 }
 
-pub fn part_1(input: String) {
-  let spring_groups = parse(input);
-
-  solve(spring_groups).println();
+fn part_1(spring_groups: &[(Vec<Spring>, Vec<usize>)]) -> Answer {
+  solve(spring_groups.to_vec()).into()
 }
 
-pub fn part_2(input: String) {
-  let springs = parse(input);
-
+fn part_2(springs: &[(Vec<Spring>, Vec<usize>)]) -> Answer {
   let springs = springs
-    .into_iter()
+    .iter()
     .map(|(springs, groups)| {
       (
         Itertools::intersperse((0..5).map(|_| springs.clone()), vec![Spring::Unknown])
@@ -65,26 +77,26 @@ pub fn part_2(input: String) {
     })
     .collect_vec();
 
-  solve(springs).println();
+  solve(springs).into()
 }
 
 fn solve(spring_groups: Vec<(Vec<Spring>, Vec<usize>)>) -> usize {
   spring_groups
     .into_iter()
-    .map(|(mut springs, groups)| {
-      solve_inner_2(&mut springs, &groups, 0, None, 0, &mut HashMap::new())
-    })
+    .map(|(springs, groups)| solve_inner_2(&springs, &groups, 0, None, 0, &mut Memo::new()))
     .sum::<usize>()
 }
 
+type Key<'a> = (&'a [usize], usize, Spring, usize);
+
 fn solve_inner_2<'a>(
-  springs: &mut [Spring],
+  springs: &[Spring],
   groups: &'a [usize],
   idx: usize,
   cur_replace: Option<Spring>,
   dmg_size: usize,
 
-  cache: &mut HashMap<(&'a [usize], usize, Spring, usize), usize>,
+  memo: &mut Memo<Key<'a>, usize>,
 ) -> usize {
   if idx == springs.len() {
     let mut group_len = groups.len();
@@ -103,17 +115,9 @@ fn solve_inner_2<'a>(
     return if group_len != 0 { 0 } else { 1 };
   }
 
-  let cur = if let Some(cur_replace) = cur_replace {
-    cur_replace
-  } else {
-    springs[idx]
-  };
-
-  if let Some(cached) = cache.get(&(groups, idx, cur, dmg_size)) {
-    return *cached;
-  };
+  let cur = cur_replace.unwrap_or(springs[idx]);
 
-  let result = match cur {
+  memo.get_or_insert_with((groups, idx, cur, dmg_size), |memo| match cur {
     Spring::Ok => {
       let groups = if dmg_size > 0 {
         if let Some((&size, groups)) = groups.split_first() {
@@ -127,16 +131,36 @@ fn solve_inner_2<'a>(
       } else {
         groups
       };
-      solve_inner_2(springs, groups, idx + 1, None, 0, cache)
+      solve_inner_2(springs, groups, idx + 1, None, 0, memo)
     }
-    Spring::Dmg => solve_inner_2(springs, groups, idx + 1, None, dmg_size + 1, cache),
+    Spring::Dmg => solve_inner_2(springs, groups, idx + 1, None, dmg_size + 1, memo),
     Spring::Unknown => {
-      solve_inner_2(springs, groups, idx, Some(Spring::Ok), dmg_size, cache)
-        + solve_inner_2(springs, groups, idx, Some(Spring::Dmg), dmg_size, cache)
+      solve_inner_2(springs, groups, idx, Some(Spring::Ok), dmg_size, memo)
+        + solve_inner_2(springs, groups, idx, Some(Spring::Dmg), dmg_size, memo)
     }
-  };
+  })
+}
 
-  cache.insert((groups, idx, cur, dmg_size), result);
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day12>(EXAMPLE, 1).unwrap(), Answer::from(21));
+  }
 
-  result
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day12>(EXAMPLE, 2).unwrap(), Answer::from(525152));
+  }
 }
@@ -3,48 +3,82 @@
  * All rights reserved
  */
 
-use itertools::Itertools;
+use anyhow::bail;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::parse;
 
-pub fn part_1(input: String) {
-  let patterns = parse(input);
+#[derive(Debug)]
+pub struct Day13;
 
+impl Solution for Day13 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 13;
+  const TITLE: &'static str = "Point of Incidence";
+
+  type Input = Vec<Vec<Vec<bool>>>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_1(input))
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_2(input))
+  }
+}
+
+fn part_1(patterns: &[Vec<Vec<bool>>]) -> Answer {
   let mut result = 0;
   for pattern in patterns {
-    let (vert, _) = find_vertical(&pattern);
-    let (horizontal, _) = find_horizontal(&pattern);
+    let (vert, _) = find_vertical(pattern);
+    let (horizontal, _) = find_horizontal(pattern);
     result += vert + horizontal * 100;
   }
-  result.println_dbg();
+  result.into()
 }
 
-pub fn part_2(input: String) {
-  let patterns = parse(input);
-
+fn part_2(patterns: &[Vec<Vec<bool>>]) -> Answer {
   let mut result = 0;
   for pattern in patterns {
-    let (_, vert_fixed) = find_vertical(&pattern);
-    let (_, horizontal) = find_horizontal(&pattern);
+    let (_, vert_fixed) = find_vertical(pattern);
+    let (_, horizontal) = find_horizontal(pattern);
     result += if vert_fixed > 0 {
       vert_fixed
     } else {
       horizontal * 100
     };
   }
-  result.println_dbg();
+  result.into()
 }
 
-fn parse(input: String) -> Vec<Vec<Vec<bool>>> {
-  input
-    .split("\n\n")
-    .map(|pattern| {
-      pattern
-        .lines()
-        .map(|line| line.chars().map(|it| matches!(it, '#')).collect_vec())
-        .collect_vec()
-    })
-    .collect_vec()
+fn parse(input: &str) -> anyhow::Result<Vec<Vec<Vec<bool>>>> {
+  let mut patterns = vec![vec![]];
+  for line in parse::lines(input) {
+    if line.is_empty() {
+      patterns.push(vec![]);
+      continue;
+    }
+
+    let row = line.parse_chars(line.text(), |it| match it {
+      '#' => Ok(true),
+      '.' => Ok(false),
+      _ => bail!("unexpected char {it:?} in pattern"),
+    })?;
+    let pattern = patterns.last_mut().unwrap();
+    if let Some(width) = pattern.first().map(Vec::len).filter(|it| *it != row.len()) {
+      return Err(line.error(format!("row has {} cells, expected {width}", row.len())));
+    }
+    pattern.push(row);
+  }
+
+  // extra blank lines leave empty patterns
+  patterns.retain(|it| !it.is_empty());
+  Ok(patterns)
 }
 
 pub fn find_vertical(pattern: &[Vec<bool>]) -> (usize, usize) {
@@ -151,3 +185,63 @@ pub fn find_horizontal(pattern: &[Vec<bool>]) -> (usize, usize) {
 
   result
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day13>(EXAMPLE, 1).unwrap(), Answer::from(405));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day13>(EXAMPLE, 2).unwrap(), Answer::from(400));
+  }
+
+  #[test]
+  fn single_row_pattern() {
+    assert_eq!(solve::<Day13>("#.##.#", 1).unwrap(), Answer::from(3));
+    assert_eq!(solve::<Day13>("#.##..", 2).unwrap(), Answer::from(3));
+    assert_eq!(solve::<Day13>("#", 1).unwrap(), Answer::from(0));
+  }
+
+  #[test]
+  fn single_column_pattern() {
+    assert_eq!(solve::<Day13>("#\n.\n.\n#", 1).unwrap(), Answer::from(200));
+    assert_eq!(solve::<Day13>("#\n.\n.\n.", 2).unwrap(), Answer::from(200));
+  }
+
+  #[test]
+  fn extra_blank_lines_between_patterns() {
+    assert_eq!(
+      solve::<Day13>("\n##\n\n\n\n##\n\n", 1).unwrap(),
+      Answer::from(2)
+    );
+  }
+
+  #[test]
+  fn ragged_rows() {
+    let err = Day13::parse("#.#\n#.\n").unwrap_err();
+    assert_eq!(err.to_string(), "line 2: row has 2 cells, expected 3");
+  }
+}
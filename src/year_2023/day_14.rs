@@ -3,92 +3,107 @@
  * All rights reserved
  */
 
+use anyhow::bail;
 use itertools::Itertools;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::point::{Direction, UPoint};
+use crate::utils::{cycle, parse};
 
 #[derive(Debug)]
-enum Item {
+pub struct Day14;
+
+impl Solution for Day14 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 14;
+  const TITLE: &'static str = "Parabolic Reflector Dish";
+
+  type Input = Vec<Vec<Item>>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_1(input))
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_2(input))
+  }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Item {
   Round,
   Square,
   Empty,
 }
 
-pub fn part_1(input: String) {
-  let plate = parse(input);
-
-  let blockers = calc_blocker_north(&plate);
+fn part_1(plate: &[Vec<Item>]) -> Answer {
+  let blockers = calc_blocker_north(plate);
 
   let mut sum = 0;
   for ((_, y), count) in blockers {
     let num = plate.len() - y;
     sum += (0..count).map(|it| num - it).sum::<usize>();
   }
-  sum.println_dbg();
+  sum.into()
 }
 
-pub fn part_2(input: String) {
-  let mut plate = parse(input);
-
-  let mut sums = vec![];
-  // choose a number that large enough to find a repeat
-  for _ in 1..=1000 {
-    let blockers = calc_blocker_north(&plate);
-    plate = apply_blockers(plate, &blockers, (0, 1));
-    // println_plate(&plate);
-    let blockers = calc_blocker_west(&plate);
-    plate = apply_blockers(plate, &blockers, (1, 0));
-    // println_plate(&plate);
-    let blockers = calc_blocker_south(&plate);
-    plate = apply_blockers(plate, &blockers, (0, -1));
-    // println_plate(&plate);
-    let blockers = calc_blocker_east(&plate);
-    plate = apply_blockers(plate, &blockers, (-1, 0));
-    // println_plate(&plate);
-
-    let mut sum = 0;
-    for ((_, y), count) in blockers {
-      sum += count * (plate.len() - y);
-    }
-    sums.push(sum);
-  }
+fn part_2(plate: &[Vec<Item>]) -> Answer {
+  const CYCLES: usize = 1_000_000_000;
 
-  let last = sums.pop().unwrap();
-  let (idx, _) = sums
+  let plate = cycle::nth(&plate.to_vec(), |it| spin_cycle(it), CYCLES);
+  north_load(&plate).into()
+}
+
+/// tilt north, west, south, then east
+fn spin_cycle(plate: &[Vec<Item>]) -> Vec<Vec<Item>> {
+  let blockers = calc_blocker_north(plate);
+  let plate = apply_blockers(plate.to_vec(), &blockers, Direction::Up);
+  let blockers = calc_blocker_west(&plate);
+  let plate = apply_blockers(plate, &blockers, Direction::Left);
+  let blockers = calc_blocker_south(&plate);
+  let plate = apply_blockers(plate, &blockers, Direction::Down);
+  let blockers = calc_blocker_east(&plate);
+  apply_blockers(plate, &blockers, Direction::Right)
+}
+
+fn north_load(plate: &[Vec<Item>]) -> usize {
+  plate
     .iter()
     .enumerate()
-    .rfind(|(_, &it)| it == last)
-    .unwrap();
-
-  let repeat_len = sums.len() - idx;
-  let len_before_repeat = idx + 1;
-  let offset = ((((1000000000 - len_before_repeat) as f64 / repeat_len as f64).fract()) * 18_f64)
-    .round() as usize;
-  sums[idx + offset].println_dbg()
+    .map(|(y, row)| {
+      let rounds = row.iter().filter(|it| matches!(it, Item::Round)).count();
+      rounds * (plate.len() - y)
+    })
+    .sum()
 }
 
-fn parse(input: String) -> Vec<Vec<Item>> {
-  input
-    .lines()
-    .map(|it| {
-      it.chars()
-        .map(|it| match it {
-          'O' => Item::Round,
-          '#' => Item::Square,
-          '.' => Item::Empty,
-          _ => {
-            unreachable!()
-          }
-        })
-        .collect_vec()
-    })
-    .collect_vec()
+fn parse(input: &str) -> anyhow::Result<Vec<Vec<Item>>> {
+  let mut plate: Vec<Vec<Item>> = vec![];
+  for line in parse::lines(input) {
+    let row = line.parse_chars(line.text(), |it| match it {
+      'O' => Ok(Item::Round),
+      '#' => Ok(Item::Square),
+      '.' => Ok(Item::Empty),
+      _ => bail!("unexpected char {it:?} in platform"),
+    })?;
+    if let Some(width) = plate.first().map(Vec::len).filter(|it| *it != row.len()) {
+      return Err(line.error(format!("row has {} cells, expected {width}", row.len())));
+    }
+    plate.push(row);
+  }
+  Ok(plate)
 }
 
+/// move every round rock towards `tilt`, stacking them up from the blockers
 fn apply_blockers(
   plate: Vec<Vec<Item>>,
   blockers: &[((usize, usize), usize)],
-  offset: (isize, isize),
+  tilt: Direction,
 ) -> Vec<Vec<Item>> {
   let mut plate = plate
     .into_iter()
@@ -103,13 +118,11 @@ fn apply_blockers(
     })
     .collect_vec();
 
-  for ((x, y), count) in blockers {
+  let offset = tilt.opposite().offset();
+  for (pos, count) in blockers {
     for i in 0..*count as isize {
-      let (x, y) = (
-        x.checked_add_signed(offset.0 * i).unwrap(),
-        y.checked_add_signed(offset.1 * i).unwrap(),
-      );
-      plate[y][x] = Item::Round;
+      let pos = UPoint::from(*pos).checked_add_signed(offset * i).unwrap();
+      plate[pos.y][pos.x] = Item::Round;
     }
   }
 
@@ -235,3 +248,37 @@ fn calc_blocker_east(plate: &[Vec<Item>]) -> Vec<((usize, usize), usize)> {
   }
   blockers
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day14>(EXAMPLE, 1).unwrap(), Answer::from(136));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day14>(EXAMPLE, 2).unwrap(), Answer::from(64));
+  }
+
+  #[test]
+  fn ragged_rows() {
+    let err = Day14::parse("O.#\n.O\n").unwrap_err();
+    assert_eq!(err.to_string(), "line 2: row has 2 cells, expected 3");
+  }
+}
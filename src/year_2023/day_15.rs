@@ -3,53 +3,100 @@
  * All rights reserved
  */
 
+use anyhow::anyhow;
 use itertools::Itertools;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::parse;
 
-pub fn part_1(input: String) {
-  input
-    .lines()
+#[derive(Debug)]
+pub struct Day15;
+
+impl Solution for Day15 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 15;
+  const TITLE: &'static str = "Lens Library";
+
+  type Input = Vec<Step>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_1(input))
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_2(input))
+  }
+}
+
+#[derive(Debug)]
+pub struct Step {
+  text: String,
+  label: String,
+  op: Operation,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Operation {
+  Remove,
+  Insert(u64),
+}
+
+fn parse(input: &str) -> anyhow::Result<Vec<Step>> {
+  let line = parse::lines(input)
     .next()
-    .unwrap()
+    .ok_or_else(|| anyhow!("empty input"))?;
+
+  line
+    .text()
     .split(',')
-    .map(hash)
-    .sum::<usize>()
-    .println_dbg();
+    .map(|item| {
+      let Some((op_idx, op)) = item.char_indices().find(|(_, it)| ['-', '='].contains(it)) else {
+        return Err(line.error_at(item, format!("expected '-' or '=' in step {item:?}")));
+      };
+      let op = match op {
+        '-' => Operation::Remove,
+        _ => Operation::Insert(line.parse(&item[op_idx + 1..])?),
+      };
+      Ok(Step {
+        text: item.to_string(),
+        label: item[..op_idx].to_string(),
+        op,
+      })
+    })
+    .collect()
 }
 
-pub fn part_2(input: String) {
-  let mut map = (0..256).map(|_| Vec::<(&str, u64)>::new()).collect_vec();
+fn part_1(steps: &[Step]) -> Answer {
+  steps.iter().map(|it| hash(&it.text)).sum::<usize>().into()
+}
 
-  let items = input.lines().next().unwrap().split(',').collect_vec();
+fn part_2(steps: &[Step]) -> Answer {
+  let mut map = (0..256).map(|_| Vec::<(&str, u64)>::new()).collect_vec();
 
-  for item in items {
-    let (op_idx, op) = item
-      .chars()
-      .find_position(|it| ['-', '='].contains(it))
-      .unwrap();
-    let label = &item[..op_idx];
+  for step in steps {
+    let label = step.label.as_str();
     let label_hash = hash(label);
     let slots = &mut map[label_hash];
     let exists = slots.iter_mut().find_position(|it| it.0 == label);
 
-    match op {
-      '-' => {
+    match step.op {
+      Operation::Remove => {
         if let Some((idx, _)) = exists {
           slots.remove(idx);
         }
       }
-      '=' => {
-        let v = item.chars().nth(op_idx + 1).unwrap().to_digit(10).unwrap() as u64;
+      Operation::Insert(v) => {
         if let Some((_, value)) = exists {
           value.1 = v;
         } else {
           slots.push((label, v))
         }
       }
-      _ => {
-        unreachable!()
-      }
     }
   }
 
@@ -64,7 +111,7 @@ pub fn part_2(input: String) {
     })
     .map(|(box_id, slot_id, focal_len)| (box_id + 1) * (slot_id + 1) * focal_len as usize)
     .sum::<usize>()
-    .println_dbg();
+    .into()
 }
 
 fn hash(item: &str) -> usize {
@@ -77,3 +124,22 @@ fn hash(item: &str) -> usize {
   }
   current
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day15>(EXAMPLE, 1).unwrap(), Answer::from(1320));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day15>(EXAMPLE, 2).unwrap(), Answer::from(145));
+  }
+}
@@ -5,66 +5,90 @@
 
 use itertools::Itertools;
 
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::parse;
+
+#[derive(Debug)]
+pub struct Day2;
+
+impl Solution for Day2 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 2;
+  const TITLE: &'static str = "Cube Conundrum";
+
+  type Input = Vec<(u64, CubeSet)>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_1(input)
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
+  }
+}
+
 #[derive(Debug, Default)]
-struct CubeSet {
+pub struct CubeSet {
   red: u64,
   green: u64,
   blue: u64,
 }
 
-fn parse(input: String) -> Vec<(u64, CubeSet)> {
-  input
-    .split('\n')
-    .map(|it| {
-      let it = it.trim_start_matches("Game ");
+fn parse(input: &str) -> anyhow::Result<Vec<(u64, CubeSet)>> {
+  parse::lines(input)
+    .map(|line| {
+      let it = line.strip_prefix("Game ")?;
 
-      let (game_id, sets) = it.split_at(it.find(':').unwrap());
-      let game_id = game_id.parse::<u64>().unwrap();
+      let (game_id, sets) = line.split_once(it, ":")?;
+      let game_id = line.parse::<u64>(game_id)?;
 
-      let color_max = sets[1..]
+      let color_max = sets
         .split(';')
         .map(|set| {
           // parse each set
           let mut cube_set = CubeSet::default();
-          set[1..]
-            .split(',')
-            .map(|it| it.trim_start())
-            .for_each(|it| {
-              // parse each cube
-              let (num, color) = it.split_at(it.find(' ').unwrap());
-              let num = num.parse::<u64>().unwrap();
-              match color.trim_start() {
-                "red" => {
-                  cube_set.red = num;
-                }
-                "green" => {
-                  cube_set.green = num;
-                }
-                "blue" => {
-                  cube_set.blue = num;
-                }
-                _ => {
-                  unreachable!()
-                }
+          for it in set.split(',').map(str::trim) {
+            // parse each cube
+            let (num, color) = line.split_once(it, " ")?;
+            let num = line.parse::<u64>(num)?;
+            let color = color.trim_start();
+            match color {
+              "red" => {
+                cube_set.red = num;
+              }
+              "green" => {
+                cube_set.green = num;
               }
-            });
-          cube_set
+              "blue" => {
+                cube_set.blue = num;
+              }
+              _ => {
+                return Err(line.error_at(color, format!("unknown color {color:?}")));
+              }
+            }
+          }
+          Ok(cube_set)
         })
-        .fold(CubeSet::default(), |acc, it| {
+        .fold_ok(CubeSet::default(), |acc, it| {
           // get max of each color
           CubeSet {
             red: acc.red.max(it.red),
             green: acc.green.max(it.green),
             blue: acc.blue.max(it.blue),
           }
-        });
-      (game_id, color_max)
+        })?;
+      Ok((game_id, color_max))
     })
-    .collect_vec()
+    .collect()
 }
 
-pub fn part_1(input: String) {
-  let result = parse(input)
+fn part_1(games: &[(u64, CubeSet)]) -> anyhow::Result<Answer> {
+  let result = games
     .iter()
     .filter_map(|(game_id, color_max)| {
       if color_max.red > 12 || color_max.green > 13 || color_max.blue > 14 {
@@ -75,14 +99,37 @@ pub fn part_1(input: String) {
     })
     .sum::<u64>();
 
-  println!("{}", result);
+  Ok(result.into())
 }
 
-pub fn part_2(input: String) {
-  let result = parse(input)
+fn part_2(games: &[(u64, CubeSet)]) -> anyhow::Result<Answer> {
+  let result = games
     .iter()
     .map(|(_, color_max)| color_max.red * color_max.green * color_max.blue)
     .sum::<u64>();
 
-  println!("{}", result);
+  Ok(result.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day2>(EXAMPLE, 1).unwrap(), Answer::from(8));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day2>(EXAMPLE, 2).unwrap(), Answer::from(2286));
+  }
 }
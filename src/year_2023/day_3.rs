@@ -3,236 +3,173 @@
  * All rights reserved
  */
 
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
-use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
-use crate::utils::{HashMapExt, OnceLockExt, Then};
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::checked::{self, Int};
+use crate::utils::parse;
 
 #[derive(Debug)]
-struct Number {
-  number: u64,
-  counted: AtomicBool,
-  counted2: Mutex<Weak<()>>,
-}
+pub struct Day3;
 
-impl Number {
-  pub fn new_uncounted(number: u64) -> Self {
-    Self {
-      number,
-      counted: AtomicBool::new(false),
-      counted2: Mutex::new(Weak::new()),
-    }
-  }
-}
+impl Solution for Day3 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 3;
+  const TITLE: &'static str = "Gear Ratios";
 
-#[derive(Debug)]
-struct GearInfo {
-  /// 0 for none, 1 for one, 2 for two, 3 for more than two
-  r#type: AtomicU8,
-  ratio: AtomicU64,
-}
+  type Input = Schematic;
 
-impl GearInfo {
-  pub fn new_none() -> Self {
-    Self {
-      r#type: AtomicU8::new(0),
-      ratio: AtomicU64::new(0),
-    }
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    Ok(parse(input))
   }
-}
 
-#[derive(Debug)]
-enum Symbol {
-  Gear(GearInfo),
-  Other,
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_1::<u64>(input)?.into())
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_2::<u64>(input)?.into())
+  }
 }
 
 #[derive(Debug)]
-enum Data {
-  Number(Arc<OnceLock<Number>>),
-  Symbol(Symbol),
+pub struct Schematic {
+  /// the digits of each number, parsed into whichever integer the part sums up in
+  numbers: Vec<String>,
+  /// index into `numbers` of each digit cell
+  number_at: HashMap<(usize, usize), usize>,
+  symbols: Vec<((usize, usize), char)>,
 }
 
-impl From<Arc<OnceLock<Number>>> for Data {
-  fn from(value: Arc<OnceLock<Number>>) -> Self {
-    Self::Number(value)
+impl Schematic {
+  /// indices of the distinct numbers around `(x, y)`
+  fn adjacent_numbers(&self, (x, y): (usize, usize)) -> Vec<usize> {
+    [-1, 0, 1]
+      .into_iter()
+      .cartesian_product([-1, 0, 1])
+      .filter_map(|(off_x, off_y)| {
+        let pos = (x.checked_add_signed(off_x)?, y.checked_add_signed(off_y)?);
+        self.number_at.get(&pos).copied()
+      })
+      .unique()
+      .collect_vec()
   }
 }
 
-type Board = Box<HashMap<(usize, usize), Data>>;
-type NumberAcc = Option<(u64, Arc<OnceLock<Number>>)>;
-
-fn try_end_number(
-  board: Board,
-  loc: (usize, usize),
-  num_acc: NumberAcc,
-  new_digit: Option<u64>,
-) -> Board {
-  if let Some((num, number)) = num_acc {
-    if let Some(new_digit) = new_digit {
-      (num * 10 + new_digit)
-        .then(|num| board.inserted_box(loc, number.inited_arc(Number::new_uncounted(num)).into()))
-    } else {
-      number
-        .inited_arc(Number::new_uncounted(num))
-        .then(|_| board)
+fn parse(input: &str) -> Schematic {
+  let mut schematic = Schematic {
+    numbers: vec![],
+    number_at: HashMap::new(),
+    symbols: vec![],
+  };
+
+  for (y, line) in parse::lines(input).enumerate() {
+    let mut chars = line.text().char_indices().peekable();
+    while let Some((x, ch)) = chars.next() {
+      match ch {
+        digit if digit.is_ascii_digit() => {
+          let mut end = x + 1;
+          while let Some((x, _)) = chars.next_if(|it| it.1.is_ascii_digit()) {
+            end = x + 1;
+          }
+          for x in x..end {
+            schematic.number_at.insert((x, y), schematic.numbers.len());
+          }
+          schematic.numbers.push(line.text()[x..end].to_string());
+        }
+        '.' => {}
+        symbol => schematic.symbols.push(((x, y), symbol)),
+      }
     }
-  } else if let Some(new_digit) = new_digit {
-    board.inserted_box(
-      loc,
-      Arc::new(OnceLock::new())
-        .inited_arc(Number::new_uncounted(new_digit))
-        .into(),
-    )
-  } else {
-    board
   }
+
+  schematic
 }
 
-fn parse(input: String) -> Board {
-  input
-    .split('\n')
-    .enumerate()
-    .fold(Board::default(), |board, (y, line)| {
-      // chars
-      line
-        .char_indices()
-        .fold((board, None), |(board, num_acc), (x, ch)| match ch {
-          // digit
-          digit if digit.is_ascii_digit() => {
-            digit
-              .to_digit(10)
-              .unwrap()
-              .then(|it| it as u64)
-              .then(|digit| {
-                if x + 1 == line.len() {
-                  try_end_number(board, (x, y), num_acc, Some(digit)).then(|acc| (acc, None))
-                } else if let Some((num, number)) = num_acc {
-                  board
-                    .inserted_box((x, y), number.clone().into())
-                    .then(|board| (board, Some((num * 10 + digit, number))))
-                } else {
-                  Arc::new(OnceLock::new())
-                    .then(|number| (board.inserted_box((x, y), number.clone().into()), number))
-                    .then(|(board, number)| (board, Some((digit, number))))
-                }
-              })
-          }
-          // symbol
-          symbol if symbol != '.' => try_end_number(board, (x, y), num_acc, None)
-            .inserted_box(
-              (x, y),
-              Data::Symbol(if symbol == '*' {
-                Symbol::Gear(GearInfo::new_none())
-              } else {
-                Symbol::Other
-              }),
-            )
-            .then(|board| (board, None)),
-          // empty
-          _ => try_end_number(board, (x, y), num_acc, None).then(|board| (board, None)),
-        })
-        .then(|(board, _)| board)
-    })
+fn part_1<T: Int>(schematic: &Schematic) -> anyhow::Result<T> {
+  let counted = schematic
+    .symbols
+    .iter()
+    .flat_map(|(pos, _)| schematic.adjacent_numbers(*pos))
+    .collect::<HashSet<_>>();
+
+  checked::sum(
+    counted
+      .into_iter()
+      .map(|it| checked::parse(&schematic.numbers[it]))
+      .collect::<anyhow::Result<Vec<_>>>()?,
+  )
 }
 
-pub fn part_1(input: String) {
-  parse(input)
-    .then(|board| {
-      board.iter().for_each(|(&(x, y), data)| match data {
-        Data::Number(_) => {}
-        Data::Symbol(_) => {
-          [-1, 0, 1]
-            .into_iter()
-            .cartesian_product([-1, 0, 1])
-            .for_each(|(off_x, off_y)| {
-              if let Some(Data::Number(number)) = board.get(&(
-                x.saturating_add_signed(off_x),
-                y.saturating_add_signed(off_y),
-              )) {
-                number.get().unwrap().counted.store(true, Ordering::Relaxed)
-              }
-            });
-        }
-      });
-      board
-        .into_iter()
-        .filter_map(|(_, data)| match data {
-          Data::Number(number)
-            if Arc::strong_count(&number) == 1
-              && number.get().unwrap().counted.load(Ordering::Relaxed) =>
-          {
-            Some(number.get().unwrap().number)
-          }
-          _ => None,
-        })
-        .sum::<u64>()
-    })
-    .println_dbg()
+fn part_2<T: Int>(schematic: &Schematic) -> anyhow::Result<T> {
+  checked::sum(
+    schematic
+      .symbols
+      .iter()
+      .filter(|(_, symbol)| *symbol == '*')
+      .filter_map(|(pos, _)| {
+        // only gears next to exactly two numbers count
+        let [a, b] = schematic.adjacent_numbers(*pos)[..] else {
+          return None;
+        };
+        let [a, b] = [a, b].map(|it| checked::parse::<T>(&schematic.numbers[it]));
+        Some(a.and_then(|a| checked::mul(&a, &b?)))
+      })
+      .collect::<anyhow::Result<Vec<_>>>()?,
+  )
 }
 
-pub fn part_2(input: String) {
-  parse(input)
-    .then(|board| {
-      board.iter().for_each(|(&(x, y), data)| match data {
-        Data::Number(_) => {}
-        Data::Symbol(symbol) => {
-          let counted = Arc::new(());
-          [-1, 0, 1]
-            .into_iter()
-            .cartesian_product([-1, 0, 1])
-            .for_each(|(off_x, off_y)| {
-              if let Some((x, y)) = x
-                .checked_add_signed(off_x)
-                .and_then(|x| y.checked_add_signed(off_y).map(|y| (x, y)))
-              {
-                if let Some(Data::Number(number)) = board.get(&(x, y)) {
-                  let mut counted2 = number.get().unwrap().counted2.lock().unwrap();
-                  if counted2.strong_count() == 0 {
-                    *counted2 = Arc::downgrade(&counted);
-                    match symbol {
-                      Symbol::Gear(gear) => match gear.r#type.load(Ordering::Relaxed) {
-                        0 => {
-                          gear.r#type.fetch_add(1, Ordering::Relaxed);
-                          gear
-                            .ratio
-                            .store(number.get().unwrap().number, Ordering::Relaxed);
-                        }
-                        1 => {
-                          gear.r#type.fetch_add(1, Ordering::Relaxed);
-                          gear.ratio.store(
-                            gear.ratio.load(Ordering::Relaxed) * number.get().unwrap().number,
-                            Ordering::Relaxed,
-                          );
-                        }
-                        2 => {
-                          gear.r#type.fetch_add(1, Ordering::Relaxed);
-                        }
-                        3 => {}
-                        _ => {
-                          unreachable!()
-                        }
-                      },
-                      Symbol::Other => {}
-                    }
-                  }
-                }
-              }
-            });
-        }
-      });
-      board
-        .into_iter()
-        .filter_map(|(_, data)| match data {
-          Data::Symbol(Symbol::Gear(gear)) if gear.r#type.load(Ordering::Relaxed) == 2 => {
-            Some(gear.ratio.load(Ordering::Relaxed))
-          }
-          _ => None,
-        })
-        .sum::<u64>()
-    })
-    .println_dbg()
+#[cfg(test)]
+mod tests {
+  use num::BigInt;
+
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day3>(EXAMPLE, 1).unwrap(), Answer::from(4361));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day3>(EXAMPLE, 2).unwrap(), Answer::from(467835));
+  }
+
+  #[test]
+  fn gear_ratio_overflow() {
+    assert_eq!(part_2::<u32>(&parse(EXAMPLE)).unwrap(), 467835);
+    assert_eq!(
+      part_2::<BigInt>(&parse(EXAMPLE)).unwrap(),
+      BigInt::from(467835)
+    );
+    // 755 * 598 alone is past u16::MAX
+    assert!(part_2::<u16>(&parse(EXAMPLE)).is_err());
+    assert!(part_1::<u8>(&parse(EXAMPLE)).is_err());
+  }
+
+  #[test]
+  fn part_numbers_past_u64() {
+    let input = parse("12345678901234567890123*2");
+    let number = "12345678901234567890123".parse::<BigInt>().unwrap();
+    assert_eq!(part_1::<BigInt>(&input).unwrap(), &number + 2);
+    assert_eq!(part_2::<BigInt>(&input).unwrap(), number * 2);
+    assert!(part_1::<u64>(&input).is_err());
+    assert!(part_2::<u64>(&input).is_err());
+  }
 }
@@ -3,11 +3,35 @@
  * All rights reserved
  */
 
-use itertools::Itertools;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::parse;
 
-use crate::utils::Then;
+#[derive(Debug)]
+pub struct Day4;
 
-struct Card {
+impl Solution for Day4 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 4;
+  const TITLE: &'static str = "Scratchcards";
+
+  type Input = Vec<Card>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_1(input))
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_2(input))
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Card {
   win: Vec<u64>,
   has: Vec<u64>,
   copies: u64,
@@ -23,35 +47,22 @@ impl Card {
   }
 }
 
-fn parse(input: String) -> Vec<Card> {
-  input
-    .lines()
-    .map(|it| it.split(':').nth(1).unwrap())
-    .map(|it| it.split('|').collect_tuple::<(_, _)>().unwrap())
-    .map(|it| (it.0, it.1.trim_start_matches('|')))
-    .map(|(win, has)| {
-      [win, has]
-        .into_iter()
-        .map(|it| {
-          it.trim()
-            .split(' ')
-            .map(str::parse::<u64>)
-            .filter_map(Result::ok)
-            .collect_vec()
-        })
-        .collect_tuple::<(_, _)>()
-        .unwrap()
+fn parse(input: &str) -> anyhow::Result<Vec<Card>> {
+  parse::lines(input)
+    .map(|line| {
+      let (_, numbers) = line.split_once(line.text(), ":")?;
+      let (win, has) = line.split_once(numbers, "|")?;
+      Ok(Card {
+        win: line.parse_numbers(win)?,
+        has: line.parse_numbers(has)?,
+        copies: 1,
+      })
     })
-    .map(|(win, has)| Card {
-      win,
-      has,
-      copies: 1,
-    })
-    .collect_vec()
+    .collect()
 }
 
-pub fn part_1(input: String) {
-  parse(input)
+fn part_1(cards: &[Card]) -> Answer {
+  cards
     .iter()
     .map(Card::matching_num)
     .map(|win_count| {
@@ -62,11 +73,11 @@ pub fn part_1(input: String) {
       }
     })
     .sum::<u64>()
-    .println()
+    .into()
 }
 
-pub fn part_2(input: String) {
-  let mut cards = parse(input);
+fn part_2(cards: &[Card]) -> Answer {
+  let mut cards = cards.to_vec();
 
   for idx in 0..cards.len() {
     let matching_num = cards[idx].matching_num();
@@ -77,5 +88,29 @@ pub fn part_2(input: String) {
       .for_each(|card| card.copies += copies)
   }
 
-  cards.iter().map(|it| it.copies).sum::<u64>().println()
+  cards.iter().map(|it| it.copies).sum::<u64>().into()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day4>(EXAMPLE, 1).unwrap(), Answer::from(13));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day4>(EXAMPLE, 2).unwrap(), Answer::from(30));
+  }
 }
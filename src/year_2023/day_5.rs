@@ -3,128 +3,250 @@
  * All rights reserved
  */
 
+use std::ops::Range;
+
+use anyhow::{anyhow, bail};
 use itertools::Itertools;
 
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::checked::{self, Int};
+use crate::utils::interval::{IntervalSet, RangeMap};
+use crate::utils::parse;
 use crate::utils::Then;
 
 #[derive(Debug)]
-struct MapItem {
-  src: u64,
-  src_end: u64,
-  off: i64,
-}
+pub struct Day5;
 
-impl MapItem {
-  pub fn new(src: u64, dst: u64, len: u64) -> Self {
-    Self {
-      src,
-      src_end: src + len,
-      off: -(src as i64 - dst as i64),
-    }
+impl Solution for Day5 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 5;
+  const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+  type Input = Almanac;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
   }
 
-  #[inline(always)]
-  pub fn is_in_range(&self, value: u64) -> bool {
-    (self.src..self.src_end).contains(&value)
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_1(input)
   }
 
-  #[inline(always)]
-  pub fn map(&self, value: u64) -> u64 {
-    value.wrapping_add_signed(self.off)
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
   }
 }
 
-fn parse(input: String) -> (Vec<u64>, Vec<Vec<MapItem>>) {
-  let mut lines = input.lines();
-  let seeds = lines.next().unwrap();
+#[derive(Debug)]
+pub struct MapItem<T> {
+  src: T,
+  src_end: T,
+  dst: T,
+}
+
+impl<T: Int> MapItem<T> {
+  /// fails if either end of the ranges doesn't fit in `T`
+  pub fn new(src: T, dst: T, len: T) -> anyhow::Result<Self> {
+    checked::add(&dst, &len)?;
+    Ok(Self {
+      src_end: checked::add(&src, &len)?,
+      src,
+      dst,
+    })
+  }
+}
 
-  let seeds = seeds
-    .split_once(':')
-    .unwrap()
-    .1
-    .trim()
-    .split(' ')
-    .map(str::parse::<u64>)
-    .map(Result::unwrap)
-    .collect_vec();
+/// seeds and the maps between them and locations
+pub type Almanac<T = u64> = (Vec<T>, Vec<Vec<MapItem<T>>>);
+
+fn parse<T: Int>(input: &str) -> anyhow::Result<Almanac<T>> {
+  let mut lines = parse::lines(input);
+  let seeds = lines.next().ok_or_else(|| anyhow!("empty input"))?;
+
+  let seeds = seeds.parse_numbers(seeds.strip_prefix("seeds:")?)?;
 
   let mut maps = vec![];
 
-  while let Some(_) = lines.next() {
+  while lines.next().is_some() {
     lines
       .take_while_ref(|it| !it.is_empty())
       .skip(1)
-      .map(|it| {
-        it.split(' ')
-          .map(str::parse::<u64>)
-          .map(Result::unwrap)
+      .map(|line| {
+        line
+          .parse_numbers::<T>(line.text())?
+          .into_iter()
           .collect_tuple::<(_, _, _)>()
-          .unwrap()
+          .ok_or_else(|| line.error("expected destination, source and length"))
+          .and_then(|(dst, src, len)| MapItem::new(src, dst, len))
       })
-      .map(|(dst, src, len)| MapItem::new(src, dst, len))
-      .sorted_by_key(|it| it.src)
-      .collect_vec()
+      .collect::<anyhow::Result<Vec<_>>>()?
       .then(|map| maps.push(map));
   }
 
-  (seeds, maps)
+  Ok((seeds, maps))
 }
 
-pub fn part_1(input: String) {
-  let (seeds, maps) = parse(input);
+/// all maps of the almanac fused into one seed to location map
+pub fn seed_to_location<T: Int>(maps: &[Vec<MapItem<T>>]) -> anyhow::Result<RangeMap<T>> {
+  maps.iter().try_fold(RangeMap::identity(), |acc, map| {
+    let map = RangeMap::new(
+      map
+        .iter()
+        .map(|it| (it.src.clone()..it.src_end.clone(), it.dst.clone())),
+    )?;
+    Ok(acc.compose(&map))
+  })
+}
 
+fn part_1((seeds, maps): &Almanac) -> anyhow::Result<Answer> {
+  let map = seed_to_location(maps)?;
   seeds
-    .into_iter()
-    .map(|it| {
-      maps.iter().fold(it, |seed, map| {
-        map
-          .iter()
-          .find(|it| it.is_in_range(seed))
-          .map(|it| it.map(seed))
-          .unwrap_or(seed)
-      })
-    })
+    .iter()
+    .map(|&it| map.get(it))
     .min()
-    .println_dbg()
+    .ok_or_else(|| anyhow!("no seeds"))
+    .map(Answer::from)
+}
+
+/// the reverse of [`seed_to_location`], fails if some locations are reached by two seeds
+pub fn location_to_seed<T: Int>(maps: &[Vec<MapItem<T>>]) -> anyhow::Result<RangeMap<T>> {
+  seed_to_location(maps)?.invert()
+}
+
+/// seeds of part 2, read as pairs of start and length
+pub fn seed_ranges<T: Int>(seeds: &[T]) -> anyhow::Result<IntervalSet<T>> {
+  if !seeds.len().is_multiple_of(2) {
+    bail!(
+      "seeds must be pairs of start and length, got {} numbers",
+      seeds.len()
+    );
+  }
+  seeds
+    .chunks(2)
+    .map(|it| Ok(it[0].clone()..checked::add(&it[0], &it[1])?))
+    .collect()
 }
 
-pub fn part_2(input: String) {
-  let (seeds, maps) = parse(input);
-
-  let mut ranges = seeds.chunks(2).map(|it| it[0]..it[0] + it[1]).collect_vec();
-
-  // map
-  for map in maps {
-    // each map item
-    let mut mapped = vec![];
-    for map_item in map {
-      // apply range
-      let mut unmapped = vec![];
-      for range in ranges {
-        let left = range.start..range.end.min(map_item.src);
-        let mid = range.start.max(map_item.src)..range.end.min(map_item.src_end);
-        let right = range.start.max(map_item.src_end)..range.end;
-        if !left.is_empty() {
-          unmapped.push(left);
-        }
-        if !mid.is_empty() {
-          mapped.push(
-            mid.start.wrapping_add_signed(map_item.off)..mid.end.wrapping_add_signed(map_item.off),
-          );
-        }
-        if !right.is_empty() {
-          unmapped.push(right);
-        }
-      }
-      ranges = unmapped;
+/// the seeds of part 2 which share one shift and contain the lowest location, and that location
+pub fn min_location_seeds<T: Int>((seeds, maps): &Almanac<T>) -> anyhow::Result<(Range<T>, T)> {
+  let map = seed_to_location(maps)?;
+  // the lowest location is always the start of a segment, as each segment is shifted as a whole
+  seed_ranges(seeds)?
+    .ranges()
+    .iter()
+    .flat_map(|it| map.segments(it.clone()))
+    .min_by(|a, b| a.1.cmp(&b.1))
+    .ok_or_else(|| anyhow!("no seeds"))
+}
+
+fn part_2(almanac: &Almanac) -> anyhow::Result<Answer> {
+  Ok(min_location_seeds(almanac)?.1.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use num::BigInt;
+
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day5>(EXAMPLE, 1).unwrap(), Answer::from(35));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day5>(EXAMPLE, 2).unwrap(), Answer::from(46));
+  }
+
+  #[test]
+  fn example_composed_map() {
+    let almanac = parse::<u64>(EXAMPLE).unwrap();
+    let map = seed_to_location(&almanac.1).unwrap();
+    assert_eq!([79, 14, 55, 13].map(|it| map.get(it)), [82, 43, 86, 35]);
+    assert!(map.breakpoints().windows(2).all(|it| it[0] < it[1]));
+
+    let inverse = location_to_seed(&almanac.1).unwrap();
+    for location in 0..120 {
+      assert_eq!(map.get(inverse.get(location)), location);
     }
-    ranges.extend(mapped);
+    assert_eq!(inverse.get(46), 82);
+
+    let (seeds, location) = min_location_seeds(&almanac).unwrap();
+    assert_eq!((seeds.start, location), (82, 46));
+    assert_eq!(map.get(seeds.end - 1), 46 + (seeds.end - 1 - 82));
+  }
+
+  #[test]
+  fn ranges_past_u128() {
+    let input = "\
+seeds: 340282366920938463463374607431768211450 10
+
+seed-to-soil map:
+0 340282366920938463463374607431768211452 10";
+    assert!(parse::<u128>(input).is_err());
+
+    let almanac = parse::<BigInt>(input).unwrap();
+    let start = "340282366920938463463374607431768211452"
+      .parse::<BigInt>()
+      .unwrap();
+    let end = &start + 8;
+    assert_eq!(
+      min_location_seeds(&almanac).unwrap(),
+      (start..end, BigInt::from(0))
+    );
   }
 
-  ranges
-    .into_iter()
-    .min_by_key(|it| it.start)
-    .unwrap()
-    .start
-    .println_dbg();
+  #[test]
+  fn ranges_past_u64() {
+    let input = "\
+seeds: 18446744073709551610 10
+
+seed-to-soil map:
+0 18446744073709551612 10";
+    assert!(parse::<u64>(input).is_err());
+
+    let almanac = parse::<u128>(input).unwrap();
+    assert_eq!(
+      min_location_seeds(&almanac).unwrap(),
+      (18446744073709551612..18446744073709551620, 0)
+    );
+  }
 }
@@ -3,43 +3,91 @@
  * All rights reserved
  */
 
+use anyhow::{anyhow, bail};
 use itertools::Itertools;
+use num::integer::Roots;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::parse;
 
-fn parse(input: String) -> Vec<(u64, u64)> {
-  let mut lines = input
-    .lines()
-    .map(|it| {
-      it.trim_start_matches("Time:")
-        .trim_start_matches("Distance:")
-    })
-    .map(|it| {
-      it.split_whitespace()
-        .map(str::parse::<u64>)
-        .map(Result::unwrap)
-        .collect_vec()
-    });
-  let times = lines.next().unwrap();
-  let distance = lines.next().unwrap();
-
-  times.into_iter().zip_eq(distance).collect_vec()
+#[derive(Debug)]
+pub struct Day6;
+
+impl Solution for Day6 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 6;
+  const TITLE: &'static str = "Wait For It";
+
+  type Input = Vec<(u64, u64)>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    Ok(part_1(input))
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
+  }
 }
 
-pub fn part_1(input: String) {
-  parse(input)
-    .into_iter()
-    .map(|(time, distance)| {
-      (1..time)
-        .filter_map(|it| ((time - it) * it > distance).then_some(()))
-        .count()
-    })
-    .product::<usize>()
-    .println_dbg();
+fn parse(input: &str) -> anyhow::Result<Vec<(u64, u64)>> {
+  let mut lines = parse::lines(input);
+  let mut next_numbers = |prefix: &str| {
+    let line = lines
+      .next()
+      .ok_or_else(|| anyhow!("missing line starting with {prefix:?}"))?;
+    line.parse_numbers::<u64>(line.strip_prefix(prefix)?)
+  };
+  let times = next_numbers("Time:")?;
+  let distance = next_numbers("Distance:")?;
+
+  if times.len() != distance.len() {
+    bail!("{} times but {} distances", times.len(), distance.len());
+  }
+
+  Ok(times.into_iter().zip(distance).collect_vec())
+}
+
+/// number of hold times `h` with `h * (time - h) > distance`
+///
+/// the winning hold times lie strictly between the roots of `h^2 - time * h + distance`, which
+/// is symmetric around `time / 2`, so only the lowest one needs to be found
+fn ways_to_win(time: u64, distance: u64) -> u64 {
+  let (time, distance) = (time as u128, distance as u128);
+  let wins = |hold: u128| hold * (time - hold) > distance;
+
+  let half = time / 2;
+  if !wins(half) {
+    return 0;
+  }
+
+  // the root rounded down is at most one off the lowest winning hold time
+  let discriminant = time * time - 4 * distance;
+  let mut lowest = (time - discriminant.sqrt()) / 2;
+  while !wins(lowest) {
+    lowest += 1;
+  }
+  while lowest > 0 && wins(lowest - 1) {
+    lowest -= 1;
+  }
+
+  (time - lowest - lowest + 1) as u64
 }
 
-pub fn part_2(input: String) {
-  let (time, distance) = parse(input).into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+fn part_1(races: &[(u64, u64)]) -> Answer {
+  races
+    .iter()
+    .map(|&(time, distance)| ways_to_win(time, distance))
+    .product::<u64>()
+    .into()
+}
+
+fn part_2(races: &[(u64, u64)]) -> anyhow::Result<Answer> {
+  let (time, distance) = races.iter().copied().unzip::<_, _, Vec<_>, Vec<_>>();
   let (time, distance) = [time, distance]
     .into_iter()
     .map(|it| {
@@ -47,13 +95,54 @@ pub fn part_2(input: String) {
         .map(u64::to_string)
         .join("")
         .parse::<u64>()
-        .unwrap()
+        .map_err(|err| anyhow!("joined number {err}"))
     })
+    .collect::<anyhow::Result<Vec<_>>>()?
+    .into_iter()
     .collect_tuple::<(_, _)>()
     .unwrap();
 
-  let left = (1..time).find(|&it| (time - it) * it > distance).unwrap();
-  let right = (1..time).rfind(|&it| (time - it) * it > distance).unwrap();
+  Ok(ways_to_win(time, distance).into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day6>(EXAMPLE, 1).unwrap(), Answer::from(288));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day6>(EXAMPLE, 2).unwrap(), Answer::from(71503));
+  }
+
+  #[test]
+  fn ways_to_win_matches_brute_force() {
+    for time in 0..80_u64 {
+      // past `time^2 / 4` nothing wins any more
+      for distance in 0..time * time / 4 + 3 {
+        let expected = (0..=time)
+          .filter(|hold| hold * (time - hold) > distance)
+          .count() as u64;
+        assert_eq!(ways_to_win(time, distance), expected, "{time} {distance}");
+      }
+    }
+  }
 
-  (right - left + 1).println_dbg();
+  #[test]
+  fn ways_to_win_large() {
+    // 10 * 10 is exactly the record, so it doesn't count
+    assert_eq!(ways_to_win(20, 100), 0);
+    assert_eq!(ways_to_win(20, 99), 1);
+    assert_eq!(ways_to_win(u64::MAX, 0), u64::MAX - 1);
+    assert_eq!(ways_to_win(4_000_000_000, 1), 3_999_999_999);
+  }
 }
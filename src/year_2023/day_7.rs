@@ -3,16 +3,42 @@
  * All rights reserved
  */
 
-use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
+use std::sync::OnceLock;
 
+use anyhow::{anyhow, bail};
 use itertools::Itertools;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::parse;
+
+#[derive(Debug)]
+pub struct Day7;
+
+impl Solution for Day7 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 7;
+  const TITLE: &'static str = "Camel Cards";
+
+  type Input = Vec<Hand>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_1(input)
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
+  }
+}
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Ord, PartialOrd, Hash)]
-enum Card {
-  J,
+pub enum Card {
   Two,
   Three,
   Four,
@@ -22,15 +48,17 @@ enum Card {
   Eight,
   Nine,
   T,
-  // part1 J loc
+  J,
   Q,
   K,
   A,
 }
 
-impl From<char> for Card {
-  fn from(value: char) -> Self {
-    match value {
+impl TryFrom<char> for Card {
+  type Error = anyhow::Error;
+
+  fn try_from(value: char) -> anyhow::Result<Self> {
+    Ok(match value {
       '2' => Card::Two,
       '3' => Card::Three,
       '4' => Card::Four,
@@ -45,9 +73,9 @@ impl From<char> for Card {
       'K' => Card::K,
       'A' => Card::A,
       _ => {
-        unreachable!()
+        bail!("unexpected char {value:?} in hand")
       }
-    }
+    })
   }
 }
 
@@ -72,68 +100,201 @@ impl Display for Card {
   }
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Ord, PartialOrd, Hash)]
-enum CardsType {
-  HighCard,
-  OnePair,
-  TwoPair,
-  ThreeOfAKind,
-  FullHouse,
-  FourOfAKind,
-  FiveOfAKind,
+/// hand categories from weakest to strongest, each a name and the largest group sizes it needs
+///
+/// a hand falls into the strongest category whose sizes its own sorted group sizes cover, so
+/// `[3, 1, 1]` is a three of a kind, and with six cards `[3, 3]` still counts as a full house
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Categories {
+  table: Vec<(String, Vec<u64>)>,
 }
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone)]
-struct CardsInfo {
-  r#type: CardsType,
-  cards: Vec<Card>,
+impl Categories {
+  pub fn new<'a>(table: impl IntoIterator<Item = (&'a str, Vec<u64>)>) -> Self {
+    Self {
+      table: table
+        .into_iter()
+        .map(|(name, sizes)| (name.to_string(), sizes))
+        .collect(),
+    }
+  }
+
+  /// the categories of Camel Cards
+  pub fn standard() -> &'static Self {
+    static STANDARD: OnceLock<Categories> = OnceLock::new();
+    STANDARD.get_or_init(|| {
+      Self::new([
+        ("high card", vec![1]),
+        ("one pair", vec![2]),
+        ("two pair", vec![2, 2]),
+        ("three of a kind", vec![3]),
+        ("full house", vec![3, 2]),
+        ("four of a kind", vec![4]),
+        ("five of a kind", vec![5]),
+      ])
+    })
+  }
+
+  /// index of the category of a hand with the group sizes `signature`, largest first
+  pub fn classify(&self, signature: &[u64]) -> Option<usize> {
+    self.table.iter().rposition(|(_, sizes)| {
+      sizes.len() <= signature.len() && sizes.iter().zip(signature).all(|(a, b)| a <= b)
+    })
+  }
+
+  pub fn name(&self, category: usize) -> &str {
+    &self.table[category].0
+  }
 }
 
-impl CardsInfo {
-  pub fn new(cards: Vec<Card>) -> Self {
-    let mut grouped = cards
+/// how hands are compared, first by category and then card by card by strength
+pub trait Rules {
+  fn hand_size(&self) -> usize {
+    5
+  }
+
+  fn categories(&self) -> &Categories {
+    Categories::standard()
+  }
+
+  /// strength of a card when comparing two hands of the same category, higher wins
+  fn card_strength(&self, card: Card) -> u8 {
+    card as u8
+  }
+
+  /// whether `card` stands in for whichever card makes the hand strongest
+  fn is_wild(&self, _card: Card) -> bool {
+    false
+  }
+
+  /// sizes of the groups of equal cards, largest first, for every way the wild cards can go
+  fn signatures(&self, cards: &[Card]) -> HashSet<Vec<u64>> {
+    let groups = cards
       .iter()
-      .into_grouping_map_by(|it| **it)
-      .fold(0u64, |acc, _, _| acc + 1)
-      .into_iter()
-      .sorted_by(|(_, count), (_, count_b)| count.cmp(count_b))
+      .filter(|it| !self.is_wild(**it))
+      .counts()
+      .into_values()
+      .map(|it| it as u64)
+      .sorted()
       .rev()
       .collect_vec();
 
-    // region part2
-    let j_count = grouped
+    // each wild card joins one of the groups or starts a new one
+    let wild = cards.len() - groups.iter().sum::<u64>() as usize;
+    (0..wild).fold(HashSet::from([groups]), |signatures, _| {
+      signatures
+        .iter()
+        .flat_map(|groups| {
+          (0..=groups.len()).map(|idx| {
+            let mut groups = groups.clone();
+            match groups.get_mut(idx) {
+              Some(size) => *size += 1,
+              None => groups.push(1),
+            }
+            groups.sort_unstable_by(|a, b| b.cmp(a));
+            groups
+          })
+        })
+        .collect()
+    })
+  }
+
+  /// the strongest category the hand can reach, the largest group is not always the best
+  /// place for the wild cards when the categories are not the standard ones
+  fn classify(&self, cards: &[Card]) -> Option<usize> {
+    self
+      .signatures(cards)
       .iter()
-      .enumerate()
-      .find(|(_, (c, _))| matches!(c, Card::J));
-    if let Some((idx, _)) = j_count {
-      if grouped.len() >= 2 {
-        let (_, count) = grouped.remove(idx);
-        grouped[0].1 += count;
-      }
+      .filter_map(|it| self.categories().classify(it))
+      .max()
+  }
+}
+
+/// wild cards are the weakest individually
+fn wild_strength(rules: &impl Rules, card: Card) -> u8 {
+  if rules.is_wild(card) {
+    0
+  } else {
+    card as u8 + 1
+  }
+}
+
+/// part 1, no wild cards
+#[derive(Debug)]
+pub struct Standard;
+
+impl Rules for Standard {}
+
+/// part 2, the `wild` cards are the weakest individually, but join any group
+#[derive(Debug)]
+pub struct Wildcards {
+  pub wild: Vec<Card>,
+}
+
+impl Wildcards {
+  pub fn jokers() -> Self {
+    Self {
+      wild: vec![Card::J],
     }
-    // endregion
-
-    let r#type = match grouped.len() {
-      1 => CardsType::FiveOfAKind,
-      2 => {
-        if grouped[0].1 == 4 {
-          CardsType::FourOfAKind
-        } else {
-          CardsType::FullHouse
-        }
-      }
-      3 => {
-        if grouped[0].1 == 3 {
-          CardsType::ThreeOfAKind
-        } else {
-          CardsType::TwoPair
-        }
-      }
-      4 => CardsType::OnePair,
-      _ => CardsType::HighCard,
-    };
+  }
+}
+
+impl Rules for Wildcards {
+  fn card_strength(&self, card: Card) -> u8 {
+    wild_strength(self, card)
+  }
 
-    Self { r#type, cards }
+  fn is_wild(&self, card: Card) -> bool {
+    self.wild.contains(&card)
+  }
+}
+
+/// any hand size and category table, with optional wild cards
+#[derive(Debug)]
+pub struct Variant {
+  pub hand_size: usize,
+  pub wild: Vec<Card>,
+  pub categories: Categories,
+}
+
+impl Rules for Variant {
+  fn hand_size(&self) -> usize {
+    self.hand_size
+  }
+
+  fn categories(&self) -> &Categories {
+    &self.categories
+  }
+
+  fn card_strength(&self, card: Card) -> u8 {
+    wild_strength(self, card)
+  }
+
+  fn is_wild(&self, card: Card) -> bool {
+    self.wild.contains(&card)
+  }
+}
+
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Clone)]
+struct CardsInfo {
+  category: usize,
+  strengths: Vec<u8>,
+  cards: Vec<Card>,
+}
+
+impl CardsInfo {
+  pub fn new(cards: &[Card], rules: &impl Rules) -> anyhow::Result<Self> {
+    let category = rules.classify(cards).ok_or_else(|| {
+      anyhow!(
+        "hand {} fits no category",
+        cards.iter().map(|it| it.to_string()).join("")
+      )
+    })?;
+    Ok(Self {
+      category,
+      strengths: cards.iter().map(|it| rules.card_strength(*it)).collect(),
+      cards: cards.to_vec(),
+    })
   }
 }
 
@@ -143,45 +304,227 @@ impl Debug for CardsInfo {
       f,
       "Cards: {{ {}, {:?} }}",
       self.cards.iter().map(|it| it.to_string()).join(""),
-      self.r#type
+      self.category
     )
   }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Hash)]
-struct Hand {
-  card: CardsInfo,
-  bid: u64,
+/// where a hand ended up, for debugging a ranking
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Ranked {
+  pub cards: String,
+  pub category: String,
+  /// 1 for the weakest hand
+  pub rank: u64,
+  pub bid: u64,
+  /// `rank * bid`
+  pub winnings: u64,
 }
 
-impl PartialOrd<Self> for Hand {
-  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-    Some(self.cmp(other))
+impl Display for Ranked {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{:>4} {} {:<15} {:>6} x {:<4} = {}",
+      self.rank, self.cards, self.category, self.bid, self.rank, self.winnings
+    )
   }
 }
 
-impl Ord for Hand {
-  fn cmp(&self, other: &Self) -> Ordering {
-    self.card.cmp(&other.card)
-  }
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct Hand {
+  cards: Vec<Card>,
+  bid: u64,
 }
 
-pub fn part_1(input: String) {
-  input
-    .lines()
-    .map(|it| {
-      it.split_once(' ').unwrap().then(|(cards, bid)| Hand {
-        card: CardsInfo::new(cards.chars().map(Card::from).collect_vec()),
-        bid: bid.parse().unwrap(),
+fn parse(input: &str) -> anyhow::Result<Vec<Hand>> {
+  parse::lines(input)
+    .map(|line| {
+      let (cards, bid) = line.split_once(line.text(), " ")?;
+      Ok(Hand {
+        cards: line.parse_chars(cards, Card::try_from)?,
+        bid: line.parse(bid)?,
       })
     })
-    .sorted()
-    .enumerate()
-    .fold(0, |acc, (idx, hand)| acc + (idx as u64 + 1) * hand.bid)
-    .println_dbg();
+    .collect()
+}
+
+/// every hand from the weakest to the strongest
+pub fn ranking(hands: &[Hand], rules: &impl Rules) -> anyhow::Result<Vec<Ranked>> {
+  if let Some(hand) = hands.iter().find(|it| it.cards.len() != rules.hand_size()) {
+    bail!(
+      "hand of {} cards, the rules play with {}",
+      hand.cards.len(),
+      rules.hand_size()
+    );
+  }
+
+  Ok(
+    hands
+      .iter()
+      .map(|it| Ok((CardsInfo::new(&it.cards, rules)?, it.bid)))
+      .collect::<anyhow::Result<Vec<_>>>()?
+      .into_iter()
+      .sorted()
+      .zip(1..)
+      .map(|((info, bid), rank)| Ranked {
+        cards: info.cards.iter().map(|it| it.to_string()).join(""),
+        category: rules.categories().name(info.category).to_string(),
+        rank,
+        bid,
+        winnings: rank * bid,
+      })
+      .collect(),
+  )
+}
+
+/// total winnings, each bid times the rank of its hand
+pub fn winnings(hands: &[Hand], rules: &impl Rules) -> anyhow::Result<u64> {
+  Ok(ranking(hands, rules)?.iter().map(|it| it.winnings).sum())
+}
+
+fn part_1(hands: &[Hand]) -> anyhow::Result<Answer> {
+  Ok(winnings(hands, &Standard)?.into())
 }
 
-pub fn part_2(input: String) {
-  // type determent and order of enum Card has changed
-  part_1(input);
+fn part_2(hands: &[Hand]) -> anyhow::Result<Answer> {
+  Ok(winnings(hands, &Wildcards::jokers())?.into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day7>(EXAMPLE, 1).unwrap(), Answer::from(6440));
+  }
+
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day7>(EXAMPLE, 2).unwrap(), Answer::from(5905));
+  }
+
+  #[test]
+  fn more_wildcards() {
+    let hands = parse(EXAMPLE).unwrap();
+    let rules = Wildcards {
+      wild: vec![Card::J, Card::T],
+    };
+    let category = |hand: &str| {
+      let cards = hand
+        .chars()
+        .map(|it| Card::try_from(it).unwrap())
+        .collect_vec();
+      rules
+        .categories()
+        .name(rules.classify(&cards).unwrap())
+        .to_string()
+    };
+    assert_eq!(category("KTJJT"), "five of a kind");
+    assert_eq!(category("32T3K"), "three of a kind");
+    assert_eq!(category("JJJJJ"), "five of a kind");
+    assert!(rules.card_strength(Card::T) < rules.card_strength(Card::Two));
+
+    // KTJJT, T55J5, QQQJA, 32T3K, KK677 from the top
+    let expected = 220 * 5 + 684 * 4 + 483 * 3 + 765 * 2 + 28;
+    assert_eq!(winnings(&hands, &rules).unwrap(), expected);
+  }
+
+  #[test]
+  fn hand_size_must_match() {
+    let hands = parse("AAAA 1").unwrap();
+    assert!(winnings(&hands, &Standard).is_err());
+  }
+
+  #[test]
+  fn ranking_report() {
+    let hands = parse(EXAMPLE).unwrap();
+    let ranking = ranking(&hands, &Standard).unwrap();
+    assert_eq!(
+      ranking.iter().map(|it| it.cards.as_str()).collect_vec(),
+      ["32T3K", "KTJJT", "KK677", "T55J5", "QQQJA"]
+    );
+    assert_eq!(ranking[2].category, "two pair");
+    assert_eq!(ranking[4].winnings, 483 * 5);
+    assert_eq!(
+      ranking[0].to_string(),
+      "   1 32T3K one pair           765 x 1    = 765"
+    );
+  }
+
+  #[test]
+  fn six_card_hands() {
+    let rules = Variant {
+      hand_size: 6,
+      wild: vec![Card::J],
+      categories: Categories::new([
+        ("high card", vec![1]),
+        ("one pair", vec![2]),
+        ("two pair", vec![2, 2]),
+        ("three pair", vec![2, 2, 2]),
+        ("three of a kind", vec![3]),
+        ("full house", vec![3, 2]),
+        ("two triples", vec![3, 3]),
+        ("four of a kind", vec![4]),
+        ("six of a kind", vec![6]),
+      ]),
+    };
+    let hands = parse("223344 1\nAAAKKK 2\nAAAAKQ 3\n2345JA 4\nQQ22JJ 5").unwrap();
+    let ranking = ranking(&hands, &rules).unwrap();
+    assert_eq!(
+      ranking
+        .iter()
+        .map(|it| (it.cards.as_str(), it.category.as_str()))
+        .collect_vec(),
+      [
+        ("2345JA", "one pair"),
+        ("223344", "three pair"),
+        ("AAAKKK", "two triples"),
+        ("QQ22JJ", "four of a kind"),
+        ("AAAAKQ", "four of a kind"),
+      ]
+    );
+    assert!(winnings(&hands, &Standard).is_err());
+
+    // five cards of a kind aren't in the table, they count as four
+    assert_eq!(rules.categories().classify(&[5, 1]), Some(7));
+    assert_eq!(Categories::new([("pair", vec![2])]).classify(&[1, 1]), None);
+  }
+
+  #[test]
+  fn wild_cards_split_across_groups() {
+    // two pair beats three of a kind here, so the jokers make two pairs
+    let rules = Variant {
+      hand_size: 5,
+      wild: vec![Card::J],
+      categories: Categories::new([
+        ("high card", vec![1]),
+        ("one pair", vec![2]),
+        ("three of a kind", vec![3]),
+        ("two pair", vec![2, 2]),
+      ]),
+    };
+    let hands = parse("AKQJJ 1\nAAAK2 2\n23456 3").unwrap();
+    let ranking = ranking(&hands, &rules).unwrap();
+    assert_eq!(
+      ranking
+        .iter()
+        .map(|it| (it.cards.as_str(), it.category.as_str()))
+        .collect_vec(),
+      [
+        ("23456", "high card"),
+        ("AAAK2", "three of a kind"),
+        ("AKQJJ", "two pair"),
+      ]
+    );
+  }
 }
@@ -3,10 +3,61 @@
  * All rights reserved
  */
 
+use anyhow::{anyhow, bail};
 use itertools::Itertools;
-use num::Integer;
 
-use crate::utils::Then;
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::cycle::{self, Cycle};
+use crate::utils::graph::DiGraph;
+use crate::utils::number_theory::{self, Schedule};
+use crate::utils::{parse, Then};
+
+/// ghosts whose walks all repeat within this many steps are simulated instead
+const SIMULATE_LIMIT: u64 = 100_000;
+
+#[derive(Debug)]
+pub struct Day8;
+
+impl Solution for Day8 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 8;
+  const TITLE: &'static str = "Haunted Wasteland";
+
+  type Input = Network;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    let (instructions, map) = parse(input)?;
+    let graph = DiGraph::new(
+      map
+        .iter()
+        .map(|(cur, (left, right))| (*cur, [*left, *right])),
+    )?;
+    let ends_with_z = graph.nodes().map(|it| it.1.ends_with('Z')).collect_vec();
+
+    Ok(Network {
+      instructions,
+      graph,
+      ends_with_z,
+    })
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_1(input)
+  }
+
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
+  }
+}
+
+#[derive(Debug)]
+pub struct Network {
+  instructions: Vec<Direction>,
+  /// successors of each node are left and right
+  graph: DiGraph,
+  ends_with_z: Vec<bool>,
+}
 
 #[derive(Debug, Copy, Clone)]
 enum Direction {
@@ -14,117 +65,267 @@ enum Direction {
   Right,
 }
 
-impl From<char> for Direction {
-  fn from(value: char) -> Self {
+impl TryFrom<char> for Direction {
+  type Error = anyhow::Error;
+
+  fn try_from(value: char) -> anyhow::Result<Self> {
     match value {
-      'L' => Self::Left,
-      'R' => Self::Right,
-      _ => unreachable!(),
+      'L' => Ok(Self::Left),
+      'R' => Ok(Self::Right),
+      _ => bail!("unexpected char {value:?} in instructions"),
     }
   }
 }
 
-pub fn part_1(input: String) {
-  let (instructions, map) = parse(&input);
-  let (map_arr, map_ends_with_z) = gen_map_arr(&map);
+fn part_1(network: &Network) -> anyhow::Result<Answer> {
+  let start = network
+    .graph
+    .node("AAA")
+    .ok_or_else(|| anyhow!("no node \"AAA\" in network"))?;
 
-  map
+  let walk = Walk::analyze(network, start);
+  walk
+    .tail_hits
     .iter()
-    .enumerate()
-    .find(|it| it.1 .0 == "AAA")
-    .map(|it| it.0 as u16)
-    .map(|it| find(&instructions, &map_arr, &map_ends_with_z, it))
-    .unwrap()
-    .println();
+    .chain(&walk.loop_hits)
+    .next()
+    .ok_or_else(|| anyhow!("walk from \"AAA\" never reaches a node ending with 'Z'"))?
+    .then(|it| Ok((*it).into()))
 }
 
-pub fn part_2(input: String) {
-  let (instructions, map) = parse(&input);
-  let (map_arr, map_ends_with_z) = gen_map_arr(&map);
+fn part_2(network: &Network) -> anyhow::Result<Answer> {
+  let walks = network
+    .graph
+    .nodes()
+    .filter(|(_, it)| it.ends_with('A'))
+    .map(|it| Walk::analyze(network, it.0))
+    .collect_vec();
+  if walks.is_empty() {
+    bail!("no node ending with 'A' in network");
+  }
 
-  map
+  // past the longest tail every ghost repeats together after the lcm of the periods
+  let horizon = walks
     .iter()
-    .enumerate()
-    .filter(|(_, it)| it.0.ends_with('A'))
-    .map(|it| it.0 as u16)
-    .map(|it| find(&instructions, &map_arr, &map_ends_with_z, it))
-    .reduce(|acc, cur| acc.lcm(&cur))
-    .unwrap()
-    .println();
+    .try_fold(1_u64, |lcm, it| {
+      Some(num::integer::lcm(lcm, it.cycle.period as u64)).filter(|it| *it <= SIMULATE_LIMIT)
+    })
+    .map(|lcm| {
+      lcm
+        + walks
+          .iter()
+          .map(|it| it.cycle.tail as u64)
+          .max()
+          .unwrap_or(0)
+    });
+
+  let common = match horizon {
+    Some(horizon) => simulate(&walks, horizon),
+    None => number_theory::first_common_time(&walks.iter().map(Walk::schedule).collect_vec()),
+  };
+  common
+    .ok_or_else(|| anyhow!("ghosts never stand on nodes ending with 'Z' at the same time"))?
+    .then(|it| Ok(it.into()))
 }
 
-fn gen_map_arr(map: &[(&str, (&str, &str))]) -> (Vec<(u16, u16)>, Vec<bool>) {
-  let mut map_arr = vec![];
-  let mut map_ends_with_z = vec![];
-  for (cur, (left, right)) in map {
-    let left = map
-      .iter()
-      .enumerate()
-      .find(|(_, it)| it.0 == *left)
-      .unwrap()
-      .0 as u16;
-    let right = map
-      .iter()
-      .enumerate()
-      .find(|(_, it)| it.0 == *right)
-      .unwrap()
-      .0 as u16;
-    let is_endswith_z = cur.ends_with('Z');
-    map_arr.push((left, right));
-    map_ends_with_z.push(is_endswith_z);
-  }
-  (map_arr, map_ends_with_z)
+/// the walk of one ghost over `(node, instruction index)` states, which has to loop eventually
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Walk {
+  /// the loop is entered after `cycle.tail` steps
+  pub cycle: Cycle,
+  /// the state the loop starts and ends with
+  pub entry: (u32, usize),
+  /// steps before the loop on a node ending with 'Z'
+  pub tail_hits: Vec<usize>,
+  /// steps within the first pass of the loop on a node ending with 'Z'
+  pub loop_hits: Vec<usize>,
 }
 
-fn find(
-  instructions: &[Direction],
-  map_arr: &[(u16, u16)],
-  map_ends_with_z: &[bool],
-  mut cur: u16,
-) -> usize {
-  for (count, ins) in instructions.iter().cycle().enumerate() {
-    if map_ends_with_z[cur as usize] {
-      return count;
+impl Walk {
+  pub fn analyze(network: &Network, start: u32) -> Self {
+    let Network {
+      instructions,
+      graph,
+      ends_with_z,
+    } = network;
+
+    let (cycle, states) = cycle::hashed(&(start, 0), |&(cur, idx)| {
+      let dirs = graph.successors(cur);
+      let next = match instructions[idx] {
+        Direction::Left => dirs[0],
+        Direction::Right => dirs[1],
+      };
+      (next, (idx + 1) % instructions.len())
+    });
+
+    let (tail_hits, loop_hits) = states
+      .iter()
+      .positions(|it| ends_with_z[it.0 as usize])
+      .partition(|it| *it < cycle.tail);
+    Self {
+      cycle,
+      entry: states[cycle.tail],
+      tail_hits,
+      loop_hits,
     }
+  }
 
-    let dirs = map_arr[cur as usize];
-    let next = match ins {
-      Direction::Left => dirs.0,
-      Direction::Right => dirs.1,
-    };
+  pub fn schedule(&self) -> Schedule {
+    Schedule {
+      once: self.tail_hits.iter().map(|it| *it as u64).collect(),
+      offsets: self.loop_hits.iter().map(|it| *it as u64).collect(),
+      period: self.cycle.period as u64,
+    }
+  }
 
-    cur = next;
+  /// whether the ghost stands on a node ending with 'Z' after `step` steps
+  pub fn hits(&self, step: u64) -> bool {
+    let step = self.cycle.reduce(step as usize);
+    self.tail_hits.contains(&step) || self.loop_hits.contains(&step)
   }
-  0
+}
+
+/// step all ghosts together, until `horizon` at most
+fn simulate(walks: &[Walk], horizon: u64) -> Option<u64> {
+  (0..horizon).find(|&step| walks.iter().all(|it| it.hits(step)))
 }
 
 type ParseResult<'a> = (Vec<Direction>, Vec<(&'a str, (&'a str, &'a str))>);
 
-fn parse(input: &str) -> ParseResult<'_> {
-  let mut lines = input.lines();
-  let instructions = lines
-    .next()
-    .unwrap()
-    .chars()
-    .map(Direction::from)
-    .collect_vec();
+fn parse(input: &str) -> anyhow::Result<ParseResult<'_>> {
+  let mut lines = parse::lines(input);
+  let instructions = lines.next().ok_or_else(|| anyhow!("empty input"))?;
+  if instructions.is_empty() {
+    return Err(instructions.error("expected at least one instruction"));
+  }
+  let instructions = instructions.parse_chars(instructions.text(), Direction::try_from)?;
 
   lines.next();
 
   let map = lines
-    .map(|it| {
-      it.split_once('=').unwrap().then(|(from, to)| {
+    .map(|line| {
+      let (from, to) = line.split_once(line.text(), "=")?;
+      let (left, right) = line.split_once(to, ",")?;
+      Ok((
+        from.trim(),
         (
-          from.trim(),
-          to.split_once(',').unwrap().then(|(left, right)| {
-            (
-              left.trim_start_matches(['(', ' ']),
-              right.trim_end_matches([')', ' ']).trim(),
-            )
-          }),
-        )
-      })
+          left.trim_start_matches(['(', ' ']),
+          right.trim_end_matches([')', ' ']).trim(),
+        ),
+      ))
     })
-    .collect_vec();
-  (instructions, map)
+    .collect::<anyhow::Result<Vec<_>>>()?;
+  Ok((instructions, map))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::solution::solve;
+
+  const EXAMPLE_1: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)";
+
+  const EXAMPLE_2: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+  const EXAMPLE_3: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+  // 11A hits at 3, 5, 7, .. and 22A at 2, 5, 8, .., the lcm of the first hits would be 6
+  const OFFSET_CYCLES: &str = "\
+L
+
+11A = (11B, 11B)
+11B = (11C, 11C)
+11C = (11Z, 11Z)
+11Z = (11D, 11D)
+11D = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22C, 22C)
+22C = (22D, 22D)
+22D = (22Z, 22Z)";
+
+  #[test]
+  fn example_1_part_1() {
+    assert_eq!(solve::<Day8>(EXAMPLE_1, 1).unwrap(), Answer::from(2));
+  }
+
+  #[test]
+  fn example_2_part_1() {
+    assert_eq!(solve::<Day8>(EXAMPLE_2, 1).unwrap(), Answer::from(6));
+  }
+
+  #[test]
+  fn example_3_part_2() {
+    assert_eq!(solve::<Day8>(EXAMPLE_3, 2).unwrap(), Answer::from(6));
+  }
+
+  #[test]
+  fn ghosts_with_offset_cycles() {
+    assert_eq!(solve::<Day8>(OFFSET_CYCLES, 2).unwrap(), Answer::from(5));
+  }
+
+  #[test]
+  fn walk_tail_and_loop() {
+    let network = Day8::parse(EXAMPLE_3).unwrap();
+    let walk = Walk::analyze(&network, 0);
+    assert_eq!(walk.cycle, Cycle { tail: 1, period: 2 });
+    assert_eq!(walk.entry, (1, 1));
+    assert_eq!((walk.tail_hits.len(), walk.loop_hits), (0, vec![2]));
+
+    // 'Z' only before the loop, then stuck on 'XXX'
+    let walk = Walk::analyze(
+      &Day8::parse("L\n\nAAZ = (XXX, XXX)\nXXX = (XXX, XXX)").unwrap(),
+      0,
+    );
+    assert_eq!(walk.cycle, Cycle { tail: 1, period: 1 });
+    assert_eq!((walk.tail_hits, walk.loop_hits.len()), (vec![0], 0));
+  }
+
+  #[test]
+  fn schedules_agree_with_simulation() {
+    for input in [EXAMPLE_3, OFFSET_CYCLES] {
+      let network = Day8::parse(input).unwrap();
+      let walks = network
+        .graph
+        .nodes()
+        .filter(|it| it.1.ends_with('A'))
+        .map(|it| Walk::analyze(&network, it.0))
+        .collect_vec();
+      let schedules = walks.iter().map(Walk::schedule).collect_vec();
+      assert_eq!(
+        number_theory::first_common_time(&schedules),
+        simulate(&walks, 1000)
+      );
+    }
+  }
+
+  #[test]
+  fn empty_instructions() {
+    let err = Day8::parse("\n\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: expected at least one instruction");
+  }
 }
@@ -3,71 +3,224 @@
  * All rights reserved
  */
 
-use itertools::Itertools;
-
-use crate::utils::Then;
-
-fn parse(input: String) -> Vec<Vec<i64>> {
-  input
-    .lines()
-    .map(|it| {
-      it.split(' ')
-        .map(|it| it.parse::<i64>().unwrap())
-        .collect_vec()
-    })
-    .collect_vec()
-}
+use num::{BigInt, BigRational, One, Signed, Zero};
 
-fn gen_diffs(history: Vec<i64>) -> Vec<Vec<i64>> {
-  let mut diffs = vec![];
-  let mut diff = history.clone();
-  loop {
-    diff = diff.windows(2).map(|it| it[1] - it[0]).collect_vec();
-    diffs.push(diff.clone());
-    if !diff.iter().any(|&it| it != 0) {
-      break;
-    }
+use crate::answer::Answer;
+use crate::solution::Solution;
+use crate::utils::checked::{self, Int};
+use crate::utils::parse;
+
+#[derive(Debug)]
+pub struct Day9;
+
+impl Solution for Day9 {
+  const YEAR: u16 = 2023;
+  const DAY: u8 = 9;
+  const TITLE: &'static str = "Mirage Maintenance";
+
+  type Input = Vec<Vec<BigInt>>;
+
+  fn parse(input: &str) -> anyhow::Result<Self::Input> {
+    parse(input)
+  }
+
+  fn part_1(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_1(input)
   }
 
-  diffs.reverse();
-  diffs.push(history);
-  diffs
+  fn part_2(input: &Self::Input) -> anyhow::Result<Answer> {
+    part_2(input)
+  }
 }
 
-pub fn part_1(input: String) {
-  let histories = parse(input);
+fn parse<T: Int>(input: &str) -> anyhow::Result<Vec<Vec<T>>> {
+  parse::lines(input)
+    .map(|line| line.parse_numbers(line.text()))
+    .collect()
+}
+
+/// the polynomial of lowest degree through a history, `history[i]` is its value at `i`
+///
+/// kept in Newton form, `p(x) = sum of diffs[k] * binomial(x, k)`, so it stays exact without
+/// any division except the ones for the binomials, which always divide evenly
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Polynomial<T> {
+  /// `k`th forward difference at position 0, without trailing zeros
+  diffs: Vec<T>,
+}
+
+impl<T: Int + Signed> Polynomial<T> {
+  pub fn fit(history: &[T]) -> anyhow::Result<Self> {
+    let mut diffs = vec![];
+    let mut diff = history.to_vec();
+    while let Some(first) = diff.first() {
+      diffs.push(first.clone());
+      diff = diff
+        .windows(2)
+        .map(|it| checked::sub(&it[1], &it[0]))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    }
 
-  let mut result = 0;
+    while diffs.last().is_some_and(Zero::is_zero) {
+      diffs.pop();
+    }
+    Ok(Self { diffs })
+  }
 
-  for history in histories {
-    let diffs = gen_diffs(history);
+  /// degree of the polynomial, `None` for the zero polynomial
+  pub fn degree(&self) -> Option<usize> {
+    self.diffs.len().checked_sub(1)
+  }
 
-    let mut cur = *diffs[0].last().unwrap();
-    for diff in diffs.iter().skip(1) {
-      cur += diff.last().unwrap();
+  pub fn eval(&self, x: &T) -> anyhow::Result<T> {
+    let mut result = T::zero();
+    let mut binomial = T::one();
+    for (k, diff) in self.diffs.iter().enumerate() {
+      if k > 0 {
+        // binomial(x, k) = binomial(x, k - 1) * (x - k + 1) / k
+        let k = checked::cast::<T>(k)?;
+        let factor = checked::add(&checked::sub(x, &k)?, &T::one())?;
+        binomial = checked::mul(&binomial, &factor)? / k;
+      }
+      result = checked::add(&result, &checked::mul(diff, &binomial)?)?;
     }
+    Ok(result)
+  }
+}
 
-    result += cur;
+impl Polynomial<BigInt> {
+  /// coefficients in the power basis, the constant first
+  pub fn coefficients(&self) -> Vec<BigRational> {
+    let mut coefficients = vec![BigRational::zero(); self.diffs.len()];
+    // `basis` holds the power basis coefficients of binomial(x, k)
+    let mut basis = vec![BigRational::one()];
+    for (k, diff) in self.diffs.iter().enumerate() {
+      for (coefficient, it) in coefficients.iter_mut().zip(&basis) {
+        *coefficient += it * BigRational::from(diff.clone());
+      }
+
+      // multiply by (x - k) / (k + 1)
+      let k = BigRational::from(BigInt::from(k));
+      let divisor = &k + BigRational::one();
+      let mut next = vec![BigRational::zero(); basis.len() + 1];
+      for (power, it) in basis.iter().enumerate() {
+        next[power + 1] += it / &divisor;
+        next[power] -= it * &k / &divisor;
+      }
+      basis = next;
+    }
+    coefficients
   }
+}
 
-  result.println();
+/// sum of each history's value at `position(history length)`
+fn extrapolate<T: Int + Signed>(
+  histories: &[Vec<T>],
+  position: impl Fn(usize) -> i64,
+) -> anyhow::Result<T> {
+  checked::sum(
+    histories
+      .iter()
+      .map(|history| Polynomial::fit(history)?.eval(&checked::cast(position(history.len()))?))
+      .collect::<anyhow::Result<Vec<_>>>()?,
+  )
 }
 
-pub fn part_2(input: String) {
-  let histories = parse(input);
+fn part_1(histories: &[Vec<BigInt>]) -> anyhow::Result<Answer> {
+  Ok(extrapolate::<BigInt>(histories, |len| len as i64)?.into())
+}
 
-  let mut result = 0;
+fn part_2(histories: &[Vec<BigInt>]) -> anyhow::Result<Answer> {
+  Ok(extrapolate::<BigInt>(histories, |_| -1)?.into())
+}
 
-  for history in histories {
-    let diffs = gen_diffs(history);
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
 
-    let mut cur = diffs[0][0];
-    for diff in diffs.iter().skip(1) {
-      cur = diff[0] - cur;
-    }
+  use super::*;
+  use crate::solution::solve;
 
-    result += cur;
+  const EXAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+  #[test]
+  fn example_part_1() {
+    assert_eq!(solve::<Day9>(EXAMPLE, 1).unwrap(), Answer::from(114));
   }
 
-  result.println();
+  #[test]
+  fn example_part_2() {
+    assert_eq!(solve::<Day9>(EXAMPLE, 2).unwrap(), Answer::from(2));
+  }
+
+  #[test]
+  fn polynomial_far_away() {
+    // n^3 - 2n + 5
+    let history = (0..6_i64)
+      .map(|n| BigInt::from(n * n * n - 2 * n + 5))
+      .collect_vec();
+    let polynomial = Polynomial::fit(&history).unwrap();
+    assert_eq!(polynomial.degree(), Some(3));
+
+    let x = BigInt::from(10_i64.pow(12));
+    assert_eq!(polynomial.eval(&x).unwrap(), &x * &x * &x - 2 * &x + 5);
+    assert_eq!(
+      polynomial.eval(&BigInt::from(-7)).unwrap(),
+      BigInt::from(-324)
+    );
+
+    let coefficients = [5, -2, 0, 1].map(|it| BigRational::from(BigInt::from(it)));
+    assert_eq!(polynomial.coefficients(), coefficients);
+  }
+
+  #[test]
+  fn polynomial_rational_coefficients() {
+    // triangular numbers, n * (n + 1) / 2
+    let history = [0, 1, 3, 6, 10].map(BigInt::from);
+    let polynomial = Polynomial::fit(&history).unwrap();
+    let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+    assert_eq!(
+      polynomial.coefficients(),
+      [BigRational::zero(), half.clone(), half]
+    );
+    assert_eq!(
+      polynomial.eval(&BigInt::from(100)).unwrap(),
+      BigInt::from(5050)
+    );
+
+    assert_eq!(Polynomial::fit(&[0, 0, 0]).unwrap().degree(), None);
+    assert_eq!(Polynomial::<i64>::fit(&[]).unwrap().eval(&3).unwrap(), 0);
+  }
+
+  #[test]
+  fn checked_extrapolation() {
+    let histories = parse::<i64>(EXAMPLE).unwrap();
+    assert_eq!(extrapolate(&histories, |len| len as i64).unwrap(), 114);
+    let small = parse::<i8>(EXAMPLE).unwrap();
+    assert_eq!(extrapolate(&small, |_| -1).unwrap(), 2);
+
+    // 231 and 2350 at position 20
+    assert!(extrapolate(&small, |_| 20).is_err());
+    let far = extrapolate(&parse::<BigInt>(EXAMPLE).unwrap(), |_| 10_i64.pow(12)).unwrap();
+    assert!(extrapolate(&histories, |_| 10_i64.pow(12)).is_err());
+    assert_eq!(far.to_string(), "333333333332833333333341500000000011");
+  }
+
+  #[test]
+  fn values_past_i64() {
+    // a line of 3 * 2^62 + n, the next value is past `i64::MAX` as well
+    let input = "13835058055282163712 13835058055282163713 13835058055282163714";
+    assert!(parse::<i64>(input).is_err());
+    assert_eq!(
+      solve::<Day9>(input, 1).unwrap(),
+      Answer::from("13835058055282163715".parse::<BigInt>().unwrap())
+    );
+    assert_eq!(
+      solve::<Day9>(input, 2).unwrap(),
+      Answer::from("13835058055282163711".parse::<BigInt>().unwrap())
+    );
+  }
 }